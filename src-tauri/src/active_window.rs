@@ -0,0 +1,367 @@
+use crate::UiState;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const ACTIVE_WINDOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// `active-window-changed` payload: the focused application and its window
+/// title. macOS reports no title, since reading it needs screen recording
+/// permission.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct ActiveWindow {
+    pub app_name: String,
+    pub title: String,
+}
+
+/// `C:\Program Files\Mozilla Firefox\firefox.exe` -> `firefox`.
+#[cfg(any(target_os = "windows", test))]
+fn app_name_from_path(path: &str) -> String {
+    let file = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    match file.rsplit_once('.') {
+        Some((stem, extension)) if extension.eq_ignore_ascii_case("exe") => stem.to_string(),
+        _ => file.to_string(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn foreground_window() -> Result<Option<ActiveWindow>, String> {
+    use std::ffi::c_void;
+
+    const PROCESS_QUERY_LIMITED_INFORMATION: u32 = 0x1000;
+    const MAX_PATH_CHARS: usize = 32_768;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetForegroundWindow() -> *mut c_void;
+        fn GetWindowTextLengthW(hwnd: *mut c_void) -> i32;
+        fn GetWindowTextW(hwnd: *mut c_void, text: *mut u16, max_count: i32) -> i32;
+        fn GetWindowThreadProcessId(hwnd: *mut c_void, process_id: *mut u32) -> u32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn OpenProcess(access: u32, inherit_handle: i32, process_id: u32) -> *mut c_void;
+        fn QueryFullProcessImageNameW(
+            process: *mut c_void,
+            flags: u32,
+            name: *mut u16,
+            size: *mut u32,
+        ) -> i32;
+        fn CloseHandle(handle: *mut c_void) -> i32;
+    }
+
+    // SAFETY: every buffer passed below is sized to the length handed to the
+    // call, and the process handle is closed exactly once.
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.is_null() {
+            return Ok(None);
+        }
+
+        let length = GetWindowTextLengthW(hwnd).max(0) as usize;
+        let mut title = vec![0_u16; length + 1];
+        let copied = GetWindowTextW(hwnd, title.as_mut_ptr(), title.len() as i32).max(0);
+        let title = String::from_utf16_lossy(&title[..copied as usize]);
+
+        let mut process_id = 0_u32;
+        GetWindowThreadProcessId(hwnd, &mut process_id);
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+        if process.is_null() {
+            return Err(format!("failed to open process {process_id}"));
+        }
+        let mut path = vec![0_u16; MAX_PATH_CHARS];
+        let mut size = path.len() as u32;
+        let queried = QueryFullProcessImageNameW(process, 0, path.as_mut_ptr(), &mut size);
+        CloseHandle(process);
+        if queried == 0 {
+            return Err(format!(
+                "failed to read the image name of process {process_id}"
+            ));
+        }
+
+        Ok(Some(ActiveWindow {
+            app_name: app_name_from_path(&String::from_utf16_lossy(&path[..size as usize])),
+            title,
+        }))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn foreground_window() -> Result<Option<ActiveWindow>, String> {
+    use std::ffi::{c_char, c_void, CStr};
+
+    type MsgSend = unsafe extern "C" fn(*mut c_void, *mut c_void) -> *mut c_void;
+
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> *mut c_void;
+        fn sel_registerName(name: *const c_char) -> *mut c_void;
+        fn objc_msgSend();
+        fn objc_autoreleasePoolPush() -> *mut c_void;
+        fn objc_autoreleasePoolPop(pool: *mut c_void);
+    }
+
+    // SAFETY: every message below takes no arguments and returns an object (or
+    // a C string for `UTF8String`), matching `MsgSend`; nil is checked before
+    // each send, and the autoreleased results are only read inside the pool.
+    unsafe {
+        let send = std::mem::transmute::<unsafe extern "C" fn(), MsgSend>(objc_msgSend);
+        let message = |receiver: *mut c_void, selector: &CStr| {
+            if receiver.is_null() {
+                receiver
+            } else {
+                send(receiver, sel_registerName(selector.as_ptr()))
+            }
+        };
+
+        let pool = objc_autoreleasePoolPush();
+        let workspace = message(objc_getClass(c"NSWorkspace".as_ptr()), c"sharedWorkspace");
+        let application = message(workspace, c"frontmostApplication");
+        let name = message(application, c"localizedName");
+        let utf8 = message(name, c"UTF8String") as *const c_char;
+        let app_name =
+            (!utf8.is_null()).then(|| CStr::from_ptr(utf8).to_string_lossy().into_owned());
+        objc_autoreleasePoolPop(pool);
+
+        Ok(app_name.map(|app_name| ActiveWindow {
+            app_name,
+            title: String::new(),
+        }))
+    }
+}
+
+/// Reads `_NET_ACTIVE_WINDOW` from the root window, then the window's
+/// `WM_CLASS` and `_NET_WM_NAME`. Only X11 (and XWayland) windows are seen.
+#[cfg(target_os = "linux")]
+mod x11 {
+    use super::ActiveWindow;
+    use once_cell::sync::OnceCell;
+    use std::ffi::{c_char, c_int, c_uchar, c_ulong, CStr};
+    use std::sync::atomic::{AtomicPtr, Ordering};
+    use x11_dl::xlib::{Display, XErrorEvent, Xlib};
+
+    type ErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+
+    const XA_WINDOW: c_ulong = 33;
+    const MAX_PROPERTY_LONGS: i64 = 1024;
+
+    static OWN_DISPLAY: AtomicPtr<Display> = AtomicPtr::new(std::ptr::null_mut());
+    static PREVIOUS_ERROR_HANDLER: OnceCell<ErrorHandler> = OnceCell::new();
+
+    /// The active window can close between two requests, and Xlib's error
+    /// handler is process-wide. Errors on our own connection are dropped (the
+    /// request just reports failure); everything else goes to the handler that
+    /// was installed before, i.e. GDK's.
+    unsafe extern "C" fn ignore_own_errors(
+        display: *mut Display,
+        event: *mut XErrorEvent,
+    ) -> c_int {
+        if display == OWN_DISPLAY.load(Ordering::SeqCst) {
+            return 0;
+        }
+        match PREVIOUS_ERROR_HANDLER.get().copied().flatten() {
+            Some(handler) => handler(display, event),
+            None => 0,
+        }
+    }
+
+    pub(super) struct X11ActiveWindow {
+        xlib: Xlib,
+        display: *mut Display,
+        net_active_window: c_ulong,
+        net_wm_name: c_ulong,
+        utf8_string: c_ulong,
+    }
+
+    impl X11ActiveWindow {
+        pub(super) fn open() -> Result<Self, String> {
+            if std::env::var_os("DISPLAY").is_none() {
+                return Err("active window detection needs an X11 display".to_string());
+            }
+            let xlib = Xlib::open().map_err(|error| format!("failed to load libX11: {error}"))?;
+
+            // SAFETY: the display is checked for null before use; the atom names
+            // are NUL-terminated literals.
+            unsafe {
+                let display = (xlib.XOpenDisplay)(std::ptr::null());
+                if display.is_null() {
+                    return Err("failed to open the X11 display".to_string());
+                }
+                OWN_DISPLAY.store(display, Ordering::SeqCst);
+                PREVIOUS_ERROR_HANDLER
+                    .get_or_init(|| (xlib.XSetErrorHandler)(Some(ignore_own_errors)));
+
+                let atom = |name: &CStr| (xlib.XInternAtom)(display, name.as_ptr(), 0);
+                Ok(Self {
+                    net_active_window: atom(c"_NET_ACTIVE_WINDOW"),
+                    net_wm_name: atom(c"_NET_WM_NAME"),
+                    utf8_string: atom(c"UTF8_STRING"),
+                    display,
+                    xlib,
+                })
+            }
+        }
+
+        /// Raw property bytes, or `None` when the window or property is gone.
+        unsafe fn property(
+            &self,
+            window: c_ulong,
+            property: c_ulong,
+            kind: c_ulong,
+        ) -> Option<(c_int, Vec<u8>)> {
+            let mut actual_kind: c_ulong = 0;
+            let mut format: c_int = 0;
+            let mut items: c_ulong = 0;
+            let mut bytes_after: c_ulong = 0;
+            let mut data: *mut c_uchar = std::ptr::null_mut();
+            let status = (self.xlib.XGetWindowProperty)(
+                self.display,
+                window,
+                property,
+                0,
+                MAX_PROPERTY_LONGS,
+                0,
+                kind,
+                &mut actual_kind,
+                &mut format,
+                &mut items,
+                &mut bytes_after,
+                &mut data,
+            );
+            if status != 0 || data.is_null() {
+                return None;
+            }
+            // 32-bit items are handed out as C longs.
+            let item_size = match format {
+                8 => 1,
+                16 => std::mem::size_of::<i16>(),
+                _ => std::mem::size_of::<c_ulong>(),
+            };
+            let bytes = std::slice::from_raw_parts(data, items as usize * item_size).to_vec();
+            (self.xlib.XFree)(data.cast());
+            (actual_kind == kind).then_some((format, bytes))
+        }
+
+        unsafe fn active_window(&self) -> Option<c_ulong> {
+            let root = (self.xlib.XDefaultRootWindow)(self.display);
+            let (format, bytes) = self.property(root, self.net_active_window, XA_WINDOW)?;
+            if format != 32 || bytes.len() < std::mem::size_of::<c_ulong>() {
+                return None;
+            }
+            let window =
+                c_ulong::from_ne_bytes(bytes[..std::mem::size_of::<c_ulong>()].try_into().ok()?);
+            (window != 0).then_some(window)
+        }
+
+        unsafe fn class_name(&self, window: c_ulong) -> String {
+            let mut hint = x11_dl::xlib::XClassHint {
+                res_name: std::ptr::null_mut(),
+                res_class: std::ptr::null_mut(),
+            };
+            if (self.xlib.XGetClassHint)(self.display, window, &mut hint) == 0 {
+                return String::new();
+            }
+            let read = |text: *mut c_char| {
+                (!text.is_null()).then(|| CStr::from_ptr(text).to_string_lossy().into_owned())
+            };
+            let name = read(hint.res_class).or_else(|| read(hint.res_name));
+            for text in [hint.res_name, hint.res_class] {
+                if !text.is_null() {
+                    (self.xlib.XFree)(text.cast());
+                }
+            }
+            name.unwrap_or_default()
+        }
+
+        unsafe fn title(&self, window: c_ulong) -> String {
+            if let Some((8, bytes)) = self.property(window, self.net_wm_name, self.utf8_string) {
+                return String::from_utf8_lossy(&bytes).into_owned();
+            }
+            let mut name: *mut c_char = std::ptr::null_mut();
+            if (self.xlib.XFetchName)(self.display, window, &mut name) == 0 || name.is_null() {
+                return String::new();
+            }
+            let title = CStr::from_ptr(name).to_string_lossy().into_owned();
+            (self.xlib.XFree)(name.cast());
+            title
+        }
+
+        pub(super) fn query(&mut self) -> Option<ActiveWindow> {
+            // SAFETY: `display` stays open for as long as `self` lives, and every
+            // buffer Xlib hands out is freed in the helper that received it.
+            unsafe {
+                let window = self.active_window()?;
+                Some(ActiveWindow {
+                    app_name: self.class_name(window),
+                    title: self.title(window),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn foreground_window() -> Result<Option<ActiveWindow>, String> {
+    Err(format!(
+        "active window detection is not supported on {}",
+        std::env::consts::OS
+    ))
+}
+
+/// Emits `active-window-changed` whenever the focused application or its window
+/// title changes; `window_reactions` evaluates its rules on that event.
+pub(crate) fn spawn_active_window_watcher(app: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("active-window-watcher".to_string())
+        .spawn(move || {
+            #[cfg(target_os = "linux")]
+            let mut connection = x11::X11ActiveWindow::open();
+            #[cfg(target_os = "linux")]
+            let mut foreground_window = || match connection.as_mut() {
+                Ok(connection) => Ok(connection.query()),
+                Err(error) => Err(error.clone()),
+            };
+
+            let mut last: Option<ActiveWindow> = None;
+            loop {
+                if app.state::<UiState>().quitting.load(Ordering::SeqCst) {
+                    break;
+                }
+                match foreground_window() {
+                    Ok(Some(window)) if last.as_ref() != Some(&window) => {
+                        let _ = app.emit("active-window-changed", &window);
+                        last = Some(window);
+                    }
+                    Ok(_) => {}
+                    Err(error) => tracing::debug!("active window poll failed: {error}"),
+                }
+                std::thread::sleep(ACTIVE_WINDOW_POLL_INTERVAL);
+            }
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start active window watcher: {error}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn app_name_drops_the_directory_and_exe_extension() {
+        assert_eq!(
+            app_name_from_path(r"C:\Program Files\Mozilla Firefox\firefox.exe"),
+            "firefox"
+        );
+        assert_eq!(app_name_from_path(r"C:\Tools\Code.EXE"), "Code");
+        assert_eq!(app_name_from_path("/usr/bin/foot"), "foot");
+        assert_eq!(app_name_from_path("my.app"), "my.app");
+    }
+}
@@ -0,0 +1,122 @@
+use crate::diagnostics::now_timestamp_ms;
+use crate::persist::{self, STORE_FILE};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+const CONFIG_BACKUP_VERSION: u32 = 1;
+const MAX_CONFIG_BACKUP_BYTES: u64 = 8 * 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigBackup {
+    version: u32,
+    #[serde(default)]
+    app_version: String,
+    #[serde(default)]
+    exported_at: u64,
+    store: Map<String, JsonValue>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigImported {
+    pub keys: Vec<String>,
+    pub merged: bool,
+    /// Where the store was saved before it was overwritten.
+    pub previous_backup: String,
+}
+
+fn current_backup(app: &AppHandle) -> Result<ConfigBackup, String> {
+    Ok(ConfigBackup {
+        version: CONFIG_BACKUP_VERSION,
+        app_version: app.package_info().version.to_string(),
+        exported_at: now_timestamp_ms(),
+        store: persist::entries(app)?,
+    })
+}
+
+fn write_backup(backup: &ConfigBackup, dest: &Path) -> Result<(), String> {
+    if let Some(parent) = dest
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("failed to create {}: {error}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(backup)
+        .map_err(|error| format!("failed to serialize config: {error}"))?;
+    fs::write(dest, json).map_err(|error| format!("failed to write {}: {error}", dest.display()))
+}
+
+fn read_backup(src: &Path) -> Result<ConfigBackup, String> {
+    let size = fs::metadata(src)
+        .map_err(|error| format!("failed to read {}: {error}", src.display()))?
+        .len();
+    if size > MAX_CONFIG_BACKUP_BYTES {
+        return Err(format!(
+            "{} is too large to be a config backup.",
+            src.display()
+        ));
+    }
+
+    let raw = fs::read_to_string(src)
+        .map_err(|error| format!("failed to read {}: {error}", src.display()))?;
+    let json: JsonValue =
+        serde_json::from_str(&raw).map_err(|error| format!("Not valid JSON: {error}"))?;
+    let version = json.get("version").and_then(JsonValue::as_u64);
+    match version {
+        Some(version) if version == u64::from(CONFIG_BACKUP_VERSION) => {}
+        Some(version) => {
+            return Err(format!(
+                "Unsupported config backup version {version}; expected {CONFIG_BACKUP_VERSION}."
+            ));
+        }
+        None => return Err("Not a config backup: missing version.".to_string()),
+    }
+    serde_json::from_value(json).map_err(|error| format!("Not a valid config backup: {error}"))
+}
+
+/// Writes the whole settings store to `dest` as one JSON file.
+#[tauri::command]
+pub fn export_config(app: AppHandle, dest: String) -> Result<String, String> {
+    let dest = PathBuf::from(dest);
+    write_backup(&current_backup(&app)?, &dest)?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+/// Loads a backup from `export_config`, either replacing the store or, with
+/// `merge`, overwriting only the keys it contains. The current store is saved
+/// next to the store file first. Backend-owned settings (shortcuts, update
+/// schedule, tray icon, library roots, window anchor, ...) are re-applied and
+/// `config-imported` tells every window to reload the rest.
+#[tauri::command]
+pub fn import_config(app: AppHandle, src: String, merge: bool) -> Result<ConfigImported, String> {
+    let backup = read_backup(Path::new(src.trim()))?;
+
+    let data_dir = crate::storage_root::data_dir(&app)?;
+    let previous_backup = data_dir.join(format!("{STORE_FILE}.{}.bak", now_timestamp_ms()));
+    write_backup(&current_backup(&app)?, &previous_backup)?;
+
+    let keys = persist::replace_entries(&app, backup.store, merge)?;
+    tracing::info!(
+        "imported config backup from {src} ({} keys, merge: {merge})",
+        keys.len()
+    );
+    crate::reapply_stored_settings(&app, "config import")?;
+    crate::model_scan::init_scan_allowlist(&app);
+    crate::library::revalidate_library_roots(&app)?;
+    crate::window_placement::reapply_last_anchor(&app);
+
+    let imported = ConfigImported {
+        keys,
+        merged: merge,
+        previous_backup: previous_backup.to_string_lossy().to_string(),
+    };
+    if let Err(error) = app.emit("config-imported", imported.clone()) {
+        tracing::warn!("failed to emit config-imported: {error}");
+    }
+    Ok(imported)
+}
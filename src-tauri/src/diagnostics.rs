@@ -1,12 +1,23 @@
-use serde::Serialize;
-use std::collections::VecDeque;
-use std::sync::{Arc, Mutex};
+use crate::model_validate::ParseBenchmark;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    Arc, Mutex, MutexGuard,
+};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const MAX_INPUT_EVENTS: usize = 50;
 const MAX_ERROR_EVENTS: usize = 50;
+const MAX_WINDOW_EVENTS: usize = 100;
+const MAX_PARSE_BENCHMARKS: usize = 20;
+const MAX_NOTES: usize = 50;
+const DEFAULT_MAX_FPS: f64 = 1_000.0;
+const DEFAULT_MAX_LOAD_MS: f64 = 600_000.0;
+/// Context filter that matches errors recorded without a context.
+pub const NO_CONTEXT: &str = "(none)";
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GlobalInputEvent {
     pub r#type: String,
@@ -18,6 +29,10 @@ pub struct GlobalInputEvent {
     pub x: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub y: Option<f64>,
+    /// Cursor path length since the previous emitted `MouseMove`, in the raw
+    /// global coordinates reported by the OS (not adjusted for monitor scale).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
     pub timestamp: u64,
 }
 
@@ -31,6 +46,31 @@ pub struct DiagnosticErrorRecord {
     pub timestamp: u64,
 }
 
+/// Something worth keeping for a bug report that is not an error, such as the
+/// outcome of a self-test.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticNote {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    pub timestamp: u64,
+}
+
+/// One observed `WindowEvent`. Consecutive moves or resizes of the same window
+/// collapse into one record holding the latest value and how many were seen.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowEventRecord {
+    pub window: String,
+    /// `Moved`, `Resized`, `Focused`, `CloseRequested` or `ScaleFactorChanged`.
+    pub kind: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    pub count: u32,
+    pub timestamp: u64,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticsSnapshot {
@@ -39,25 +79,139 @@ pub struct DiagnosticsSnapshot {
     pub fps: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub model_load_ms: Option<f64>,
+    /// `benchmark_model_parse` results, oldest first.
+    pub parse_benchmarks: Vec<ParseBenchmark>,
     pub recent_errors: Vec<DiagnosticErrorRecord>,
+    pub notes: Vec<DiagnosticNote>,
+    /// rdev event kind -> count of events the listener saw but doesn't forward.
+    pub dropped_input_events: BTreeMap<String, u64>,
+    pub window_events: Vec<WindowEventRecord>,
+    /// Set while `set_metrics_frozen` holds `fps` and `model_load_ms` in place.
+    pub metrics_frozen: bool,
+}
+
+/// How much of an input event survives into the diagnostics buffer. Live
+/// `global-input` events are never redacted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum InputAnonymization {
+    /// Stored as received.
+    None = 0,
+    /// `keyCode` becomes `"key"`; type, button, coordinates and timing are kept.
+    Keys = 1,
+    /// As `Keys`, and `x`, `y` and `distance` are removed as well.
+    Full = 2,
+}
+
+impl InputAnonymization {
+    pub fn parse(level: &str) -> Result<Self, String> {
+        match level {
+            "none" => Ok(Self::None),
+            "keys" => Ok(Self::Keys),
+            "full" => Ok(Self::Full),
+            _ => Err(format!(
+                "unknown anonymization level {level}; expected none, keys or full"
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Keys => "keys",
+            Self::Full => "full",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Keys,
+            2 => Self::Full,
+            _ => Self::None,
+        }
+    }
+
+    pub(crate) fn apply(self, mut event: GlobalInputEvent) -> GlobalInputEvent {
+        if self == Self::None {
+            return event;
+        }
+        if event.key_code.is_some() {
+            event.key_code = Some("key".to_string());
+        }
+        if self == Self::Full {
+            event.x = None;
+            event.y = None;
+            event.distance = None;
+        }
+        event
+    }
 }
 
-#[derive(Default)]
 pub struct DiagnosticsState {
     inner: Mutex<DiagnosticsInner>,
+    /// When off, input events are still forwarded live but not retained here.
+    input_recording: AtomicBool,
+    input_anonymization: AtomicU8,
+}
+
+impl Default for DiagnosticsState {
+    fn default() -> Self {
+        Self {
+            inner: Mutex::new(DiagnosticsInner::default()),
+            input_recording: AtomicBool::new(true),
+            input_anonymization: AtomicU8::new(InputAnonymization::None as u8),
+        }
+    }
+}
+
+/// Fixed buffer sizes and live recording settings, for `get_effective_config`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticsConfig {
+    pub max_input_events: usize,
+    pub max_error_events: usize,
+    pub max_window_events: usize,
+    pub max_parse_benchmarks: usize,
+    pub max_notes: usize,
+    pub metric_bounds: MetricBounds,
+    pub input_recording: bool,
+    pub input_anonymization: &'static str,
+}
+
+/// Upper clamps applied to reported metrics; the lower bound is always 0.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricBounds {
+    pub max_fps: f64,
+    pub max_load_ms: f64,
+}
+
+impl Default for MetricBounds {
+    fn default() -> Self {
+        Self {
+            max_fps: DEFAULT_MAX_FPS,
+            max_load_ms: DEFAULT_MAX_LOAD_MS,
+        }
+    }
 }
 
 #[derive(Default)]
 struct DiagnosticsInner {
     input_events: VecDeque<GlobalInputEvent>,
     recent_errors: VecDeque<DiagnosticErrorRecord>,
+    notes: VecDeque<DiagnosticNote>,
+    window_events: VecDeque<WindowEventRecord>,
     fps: Option<f64>,
     model_load_ms: Option<f64>,
+    parse_benchmarks: VecDeque<ParseBenchmark>,
+    dropped_input_events: BTreeMap<String, u64>,
+    metric_bounds: MetricBounds,
+    metrics_frozen: bool,
 }
 
 pub type SharedDiagnosticsState = Arc<DiagnosticsState>;
 
-fn now_timestamp_ms() -> u64 {
+pub(crate) fn now_timestamp_ms() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .map(|value| value.as_millis() as u64)
@@ -75,18 +229,161 @@ fn clamp_metric(value: f64, min: f64, max: f64) -> f64 {
     value.max(min).min(max)
 }
 
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Renders input events as `timestamp,type,key,button,x,y`; missing values are blank.
+pub fn input_events_csv(events: &[GlobalInputEvent]) -> String {
+    let mut csv = String::from("timestamp,type,key,button,x,y\n");
+    for event in events {
+        let optional = |value: Option<&str>| value.map(csv_field).unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            event.timestamp,
+            csv_field(&event.r#type),
+            optional(event.key_code.as_deref()),
+            optional(event.button.as_deref()),
+            optional(event.x.map(|x| x.to_string()).as_deref()),
+            optional(event.y.map(|y| y.to_string()).as_deref()),
+        ));
+    }
+    csv
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+/// A bug-report summary of `snapshot`. Input is reduced to counts per event
+/// type so no keystrokes or positions end up in a pasted issue.
+pub fn diagnostics_markdown(snapshot: &DiagnosticsSnapshot, app: &str, version: &str) -> String {
+    let metric = |value: Option<f64>, unit: &str| {
+        value
+            .map(|value| format!("{value:.1}{unit}"))
+            .unwrap_or_else(|| "n/a".to_string())
+    };
+
+    let mut report = String::from("## Diagnostics\n\n");
+    report.push_str(&format!("- App: {app} {version}\n"));
+    report.push_str(&format!(
+        "- OS: {} ({})\n",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    ));
+    report.push_str(&format!("- FPS: {}\n", metric(snapshot.fps, "")));
+    report.push_str(&format!(
+        "- Model load: {}\n",
+        metric(snapshot.model_load_ms, " ms")
+    ));
+    if snapshot.metrics_frozen {
+        report.push_str("- Metrics: paused\n");
+    }
+
+    report.push_str("\n### Recent errors\n\n");
+    if snapshot.recent_errors.is_empty() {
+        report.push_str("None.\n");
+    } else {
+        report.push_str("| Time (ms) | Level | Context | Message |\n|---|---|---|---|\n");
+        for error in &snapshot.recent_errors {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                error.timestamp,
+                markdown_cell(&error.level),
+                markdown_cell(error.context.as_deref().unwrap_or("")),
+                markdown_cell(&error.message),
+            ));
+        }
+    }
+
+    let mut counts: BTreeMap<&str, u64> = BTreeMap::new();
+    for event in &snapshot.input_events {
+        *counts.entry(event.r#type.as_str()).or_default() += 1;
+    }
+    report.push_str("\n### Recent input\n\n");
+    if counts.is_empty() && snapshot.dropped_input_events.is_empty() {
+        report.push_str("No input recorded.\n");
+    }
+    for (event_type, count) in &counts {
+        report.push_str(&format!("- {event_type}: {count}\n"));
+    }
+    for (event_type, count) in &snapshot.dropped_input_events {
+        report.push_str(&format!("- {event_type} (dropped): {count}\n"));
+    }
+
+    report.push_str("\n### Recent window events\n\n");
+    if snapshot.window_events.is_empty() {
+        report.push_str("None.\n");
+    } else {
+        report.push_str("| Time (ms) | Window | Event | Detail | Count |\n|---|---|---|---|---|\n");
+        for event in &snapshot.window_events {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                event.timestamp,
+                markdown_cell(&event.window),
+                event.kind,
+                markdown_cell(event.detail.as_deref().unwrap_or("")),
+                event.count,
+            ));
+        }
+    }
+    report
+}
+
 impl DiagnosticsState {
+    /// A panic while recording must not silence diagnostics for the rest of the
+    /// session, so a poisoned lock is recovered (and logged) instead of skipped.
+    fn lock_inner(&self) -> MutexGuard<'_, DiagnosticsInner> {
+        self.inner.lock().unwrap_or_else(|poisoned| {
+            tracing::warn!("diagnostics state was poisoned by a panic; recovering");
+            self.inner.clear_poison();
+            poisoned.into_inner()
+        })
+    }
+
     pub fn record_input_event(&self, event: GlobalInputEvent) {
-        let Ok(mut inner) = self.inner.lock() else {
-            return;
-        };
+        let event = self.input_anonymization().apply(event);
+        let mut inner = self.lock_inner();
         push_bounded(&mut inner.input_events, MAX_INPUT_EVENTS, event);
     }
 
+    pub fn input_recording(&self) -> bool {
+        self.input_recording.load(Ordering::Relaxed)
+    }
+
+    /// Turning recording off also forgets the events retained so far.
+    pub fn set_input_recording(&self, enabled: bool) {
+        self.input_recording.store(enabled, Ordering::Relaxed);
+        if !enabled {
+            self.lock_inner().input_events.clear();
+        }
+    }
+
+    pub fn input_anonymization(&self) -> InputAnonymization {
+        InputAnonymization::from_u8(self.input_anonymization.load(Ordering::Relaxed))
+    }
+
+    /// Applies to events recorded from now on; already retained events keep
+    /// whatever level they were stored with.
+    pub fn set_input_anonymization(&self, level: InputAnonymization) {
+        self.input_anonymization
+            .store(level as u8, Ordering::Relaxed);
+    }
+
+    pub fn record_dropped_input(&self, kind: &str) {
+        let mut inner = self.lock_inner();
+        *inner
+            .dropped_input_events
+            .entry(kind.to_string())
+            .or_default() += 1;
+    }
+
     pub fn record_error(&self, level: String, message: String, context: Option<String>) {
-        let Ok(mut inner) = self.inner.lock() else {
-            return;
-        };
+        let mut inner = self.lock_inner();
         let record = DiagnosticErrorRecord {
             level,
             message,
@@ -96,39 +393,241 @@ impl DiagnosticsState {
         push_bounded(&mut inner.recent_errors, MAX_ERROR_EVENTS, record);
     }
 
+    pub fn record_note(&self, message: String, context: Option<String>) {
+        let note = DiagnosticNote {
+            message,
+            context,
+            timestamp: now_timestamp_ms(),
+        };
+        push_bounded(&mut self.lock_inner().notes, MAX_NOTES, note);
+    }
+
+    pub fn record_window_event(&self, window: &str, kind: &'static str, detail: Option<String>) {
+        let timestamp = now_timestamp_ms();
+        let mut inner = self.lock_inner();
+        if let Some(last) = inner.window_events.back_mut() {
+            let coalesces = matches!(kind, "Moved" | "Resized");
+            if coalesces && last.kind == kind && last.window == window {
+                last.detail = detail;
+                last.count = last.count.saturating_add(1);
+                last.timestamp = timestamp;
+                return;
+            }
+        }
+        let record = WindowEventRecord {
+            window: window.to_string(),
+            kind,
+            detail,
+            count: 1,
+            timestamp,
+        };
+        push_bounded(&mut inner.window_events, MAX_WINDOW_EVENTS, record);
+    }
+
+    pub fn window_events(&self) -> Vec<WindowEventRecord> {
+        self.lock_inner().window_events.iter().cloned().collect()
+    }
+
+    /// The newest `limit` errors whose context equals `context` (or that have
+    /// none, for [`NO_CONTEXT`]), oldest first like the snapshot.
+    pub fn errors_by_context(&self, context: &str, limit: usize) -> Vec<DiagnosticErrorRecord> {
+        let inner = self.lock_inner();
+        let mut matching: Vec<DiagnosticErrorRecord> = inner
+            .recent_errors
+            .iter()
+            .rev()
+            .filter(|record| record.context.as_deref().unwrap_or(NO_CONTEXT) == context)
+            .take(limit)
+            .cloned()
+            .collect();
+        matching.reverse();
+        matching
+    }
+
+    /// Event counts by type among the retained events stamped within `window_ms`
+    /// of `now_ms`. Only the last [`MAX_INPUT_EVENTS`] events are kept, so a busy
+    /// minute is under-counted.
+    pub fn input_event_histogram(&self, window_ms: u64, now_ms: u64) -> HashMap<String, u64> {
+        let since = now_ms.saturating_sub(window_ms);
+        let mut counts = HashMap::new();
+        for event in self
+            .lock_inner()
+            .input_events
+            .iter()
+            .filter(|event| event.timestamp >= since)
+        {
+            *counts.entry(event.r#type.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
     pub fn set_metrics(&self, fps: Option<f64>, model_load_ms: Option<f64>) {
-        let Ok(mut inner) = self.inner.lock() else {
+        let mut inner = self.lock_inner();
+        if inner.metrics_frozen {
             return;
-        };
+        }
 
         if let Some(value) = fps {
             if value.is_finite() {
-                inner.fps = Some(clamp_metric(value, 0.0, 1_000.0));
+                inner.fps = Some(clamp_metric(value, 0.0, inner.metric_bounds.max_fps));
             }
         }
 
         if let Some(value) = model_load_ms {
             if value.is_finite() {
-                inner.model_load_ms = Some(clamp_metric(value, 0.0, 600_000.0));
+                let max_load_ms = inner.metric_bounds.max_load_ms;
+                inner.model_load_ms = Some(clamp_metric(value, 0.0, max_load_ms));
             }
         }
     }
 
-    pub fn snapshot(&self) -> DiagnosticsSnapshot {
-        let Ok(inner) = self.inner.lock() else {
-            return DiagnosticsSnapshot {
-                input_events: Vec::new(),
-                fps: None,
-                model_load_ms: None,
-                recent_errors: Vec::new(),
-            };
+    pub fn record_parse_benchmark(&self, benchmark: ParseBenchmark) {
+        let mut inner = self.lock_inner();
+        push_bounded(&mut inner.parse_benchmarks, MAX_PARSE_BENCHMARKS, benchmark);
+    }
+
+    /// While frozen, reported metrics are dropped and the last values stay put.
+    pub fn set_metrics_frozen(&self, frozen: bool) {
+        self.lock_inner().metrics_frozen = frozen;
+    }
+
+    /// Applies to values reported from now on; stored metrics are not re-clamped.
+    pub fn set_metric_bounds(
+        &self,
+        max_fps: f64,
+        max_load_ms: f64,
+    ) -> Result<MetricBounds, String> {
+        if !(max_fps.is_finite() && max_fps > 0.0) {
+            return Err("maxFps must be a positive, finite number".to_string());
+        }
+        if !(max_load_ms.is_finite() && max_load_ms > 0.0) {
+            return Err("maxLoadMs must be a positive, finite number".to_string());
+        }
+
+        let bounds = MetricBounds {
+            max_fps,
+            max_load_ms,
         };
+        self.lock_inner().metric_bounds = bounds;
+        Ok(bounds)
+    }
+
+    pub fn config(&self) -> DiagnosticsConfig {
+        DiagnosticsConfig {
+            max_input_events: MAX_INPUT_EVENTS,
+            max_error_events: MAX_ERROR_EVENTS,
+            max_window_events: MAX_WINDOW_EVENTS,
+            max_parse_benchmarks: MAX_PARSE_BENCHMARKS,
+            max_notes: MAX_NOTES,
+            metric_bounds: self.lock_inner().metric_bounds,
+            input_recording: self.input_recording(),
+            input_anonymization: self.input_anonymization().as_str(),
+        }
+    }
+
+    pub fn fps(&self) -> Option<f64> {
+        self.lock_inner().fps
+    }
+
+    pub fn snapshot(&self) -> DiagnosticsSnapshot {
+        let inner = self.lock_inner();
 
         DiagnosticsSnapshot {
             input_events: inner.input_events.iter().cloned().collect(),
             fps: inner.fps,
             model_load_ms: inner.model_load_ms,
+            parse_benchmarks: inner.parse_benchmarks.iter().cloned().collect(),
             recent_errors: inner.recent_errors.iter().cloned().collect(),
+            notes: inner.notes.iter().cloned().collect(),
+            dropped_input_events: inner.dropped_input_events.clone(),
+            window_events: inner.window_events.iter().cloned().collect(),
+            metrics_frozen: inner.metrics_frozen,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_survives_a_poisoned_lock() {
+        let state = Arc::new(DiagnosticsState::default());
+        let poisoner = Arc::clone(&state);
+        let result = std::thread::spawn(move || {
+            let _guard = poisoner.inner.lock().unwrap();
+            panic!("poison the diagnostics lock");
+        })
+        .join();
+        assert!(result.is_err());
+        assert!(state.inner.is_poisoned());
+
+        state.record_error("error".to_string(), "after panic".to_string(), None);
+        assert!(!state.inner.is_poisoned());
+
+        let snapshot = state.snapshot();
+        assert_eq!(snapshot.recent_errors.len(), 1);
+        assert_eq!(snapshot.recent_errors[0].message, "after panic");
+    }
+
+    #[test]
+    fn histogram_counts_only_events_inside_the_window() {
+        let state = DiagnosticsState::default();
+        for (r#type, timestamp) in [
+            ("KeyPress", 1_000),
+            ("KeyPress", 9_500),
+            ("KeyPress", 9_800),
+            ("MouseMove", 9_900),
+        ] {
+            state.record_input_event(GlobalInputEvent {
+                r#type: r#type.to_string(),
+                key_code: None,
+                button: None,
+                x: None,
+                y: None,
+                distance: None,
+                timestamp,
+            });
+        }
+
+        let histogram = state.input_event_histogram(1_000, 10_000);
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram["KeyPress"], 2);
+        assert_eq!(histogram["MouseMove"], 1);
+    }
+
+    #[test]
+    fn consecutive_moves_of_one_window_collapse() {
+        let state = DiagnosticsState::default();
+        state.record_window_event("main", "Moved", Some("0,0".to_string()));
+        state.record_window_event("main", "Moved", Some("5,5".to_string()));
+        state.record_window_event("settings", "Moved", Some("1,1".to_string()));
+        state.record_window_event("main", "Focused", Some("true".to_string()));
+        state.record_window_event("main", "Focused", Some("false".to_string()));
+
+        let events = state.window_events();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0].count, 2);
+        assert_eq!(events[0].detail.as_deref(), Some("5,5"));
+        assert_eq!(events[1].window, "settings");
+        assert_eq!(events[3].detail.as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn frozen_metrics_ignore_reports_until_unfrozen() {
+        let state = DiagnosticsState::default();
+        state.set_metrics(Some(60.0), Some(120.0));
+        state.set_metrics_frozen(true);
+        state.set_metrics(Some(30.0), Some(900.0));
+
+        let snapshot = state.snapshot();
+        assert!(snapshot.metrics_frozen);
+        assert_eq!(snapshot.fps, Some(60.0));
+        assert_eq!(snapshot.model_load_ms, Some(120.0));
+
+        state.set_metrics_frozen(false);
+        state.set_metrics(Some(30.0), None);
+        assert_eq!(state.fps(), Some(30.0));
+        assert!(!state.snapshot().metrics_frozen);
+    }
+}
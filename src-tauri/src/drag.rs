@@ -0,0 +1,424 @@
+use crate::diagnostics::GlobalInputEvent;
+use crate::main_window;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition};
+
+// The OS drag loop doesn't report a release, so a pause in `Moved` events ends the drag.
+const DRAG_END_IDLE: Duration = Duration::from_millis(150);
+const DRAG_END_POLL_INTERVAL: Duration = Duration::from_millis(40);
+// How long after `start_window_drag` the first `Moved` may arrive and still
+// count; a press that never moves the window must not arm a later nudge.
+const DRAG_ARM_TIMEOUT: Duration = Duration::from_secs(1);
+const MAX_DRAG_SAMPLES: usize = 6;
+const DROP_FLOOR_THRESHOLD_PX: i32 = 64;
+// A press only becomes a drag once the pointer leaves this radius, so a click
+// with a little jitter stays a click.
+const BUTTON_DRAG_THRESHOLD_PX: f64 = 4.0;
+
+/// Pointer drags whose `drag-start` the reaction cooldown held back, so their
+/// `drag-end` is held back too.
+static SUPPRESSED_POINTER_DRAGS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+#[derive(Default)]
+struct DragTracker {
+    samples: VecDeque<(Instant, PhysicalPosition<i32>)>,
+    /// When the frontend handed the window to the OS drag loop.
+    armed_at: Option<Instant>,
+    watching: bool,
+    /// The current run of moves counts as a drag.
+    started: bool,
+}
+
+#[derive(Default)]
+pub struct DragState {
+    tracker: Mutex<DragTracker>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DragEndPayload {
+    /// `"window"` for a pet window drag; pointer drags use `ButtonDragPayload`.
+    source: &'static str,
+    x: i32,
+    y: i32,
+    /// Release velocity in physical pixels per second.
+    velocity_x: f64,
+    velocity_y: f64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DragStartPayload {
+    source: &'static str,
+    x: i32,
+    y: i32,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PetDroppedPayload {
+    distance_to_floor: i32,
+    velocity_x: f64,
+    velocity_y: f64,
+}
+
+/// `drag-start` / `drag-end` payload for a global mouse drag with one button.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ButtonDragPayload {
+    /// Always `"pointer"`, to tell these apart from window `drag-end` events.
+    source: &'static str,
+    button: String,
+    x: f64,
+    y: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ButtonDragEvent {
+    Start(ButtonDragPayload),
+    End(ButtonDragPayload),
+}
+
+impl ButtonDragEvent {
+    pub(crate) fn emit(self, app: &AppHandle) {
+        let Ok(mut suppressed) = SUPPRESSED_POINTER_DRAGS.lock() else {
+            return;
+        };
+        let (name, payload) = match self {
+            Self::Start(payload) => {
+                let reaction = format!("drag:pointer:{}", payload.button);
+                if !crate::reaction_cooldown::allow_reaction(&reaction) {
+                    suppressed.insert(payload.button);
+                    return;
+                }
+                ("drag-start", payload)
+            }
+            Self::End(payload) => {
+                if suppressed.remove(&payload.button) {
+                    return;
+                }
+                ("drag-end", payload)
+            }
+        };
+        drop(suppressed);
+        crate::input_listener::emit_to_frontend(app, name, payload);
+    }
+}
+
+struct PressedButton {
+    /// Pointer position at press time; `None` until the first move is seen.
+    origin: Option<(f64, f64)>,
+    dragging: bool,
+}
+
+/// Turns the forwarded input stream into per-button drags. Each held button is
+/// tracked on its own, so pressing a second button mid-drag starts (and later
+/// ends) a separate drag instead of confusing the first.
+#[derive(Default)]
+pub(crate) struct ButtonDragTracker {
+    pointer: Option<(f64, f64)>,
+    pressed: BTreeMap<String, PressedButton>,
+}
+
+impl ButtonDragTracker {
+    fn payload(&self, button: &str) -> ButtonDragPayload {
+        let (x, y) = self.pointer.unwrap_or_default();
+        ButtonDragPayload {
+            source: "pointer",
+            button: button.to_string(),
+            x,
+            y,
+        }
+    }
+
+    pub(crate) fn apply(&mut self, event: &GlobalInputEvent) -> Vec<ButtonDragEvent> {
+        match (event.r#type.as_str(), event.button.as_deref()) {
+            ("ButtonPress", Some(button)) => {
+                let pressed = PressedButton {
+                    origin: self.pointer,
+                    dragging: false,
+                };
+                self.pressed.insert(button.to_string(), pressed);
+                Vec::new()
+            }
+            ("ButtonRelease", Some(button)) => match self.pressed.remove(button) {
+                Some(pressed) if pressed.dragging => {
+                    vec![ButtonDragEvent::End(self.payload(button))]
+                }
+                _ => Vec::new(),
+            },
+            ("MouseMove", _) => {
+                let (Some(x), Some(y)) = (event.x, event.y) else {
+                    return Vec::new();
+                };
+                self.pointer = Some((x, y));
+
+                let mut started = Vec::new();
+                for (button, pressed) in &mut self.pressed {
+                    if pressed.dragging {
+                        continue;
+                    }
+                    let (origin_x, origin_y) = *pressed.origin.get_or_insert((x, y));
+                    if (x - origin_x).hypot(y - origin_y) >= BUTTON_DRAG_THRESHOLD_PX {
+                        pressed.dragging = true;
+                        started.push(button.clone());
+                    }
+                }
+                started
+                    .iter()
+                    .map(|button| ButtonDragEvent::Start(self.payload(button)))
+                    .collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Velocity over the last few samples; a single sample means no real drag happened.
+fn release_velocity(samples: &VecDeque<(Instant, PhysicalPosition<i32>)>) -> Option<(f64, f64)> {
+    let (first_at, first) = samples.front()?;
+    let (last_at, last) = samples.back()?;
+    let elapsed = last_at.duration_since(*first_at).as_secs_f64();
+    if samples.len() < 2 || elapsed <= 0.0 {
+        return None;
+    }
+    Some((
+        f64::from(last.x - first.x) / elapsed,
+        f64::from(last.y - first.y) / elapsed,
+    ))
+}
+
+fn distance_to_floor(app: &AppHandle) -> Result<Option<i32>, String> {
+    let window = main_window(app, "drag-end")?;
+    let Some(monitor) = window
+        .current_monitor()
+        .map_err(|error| error.to_string())?
+    else {
+        return Ok(None);
+    };
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let work_area = monitor.work_area();
+    let floor = work_area.position.y + work_area.size.height as i32;
+    Ok(Some(floor - (position.y + size.height as i32)))
+}
+
+/// Emits `drag-end`, then `pet-dropped` near the floor unless the reaction
+/// cooldown holds it back, and snaps. `drag-start`/`drag-end` always go out:
+/// the pet window defers snapping between them.
+fn finish_drag(app: &AppHandle, samples: VecDeque<(Instant, PhysicalPosition<i32>)>) {
+    let (Some((velocity_x, velocity_y)), Some((_, position))) =
+        (release_velocity(&samples), samples.back())
+    else {
+        return;
+    };
+    let _ = app.emit(
+        "drag-end",
+        DragEndPayload {
+            source: "window",
+            x: position.x,
+            y: position.y,
+            velocity_x,
+            velocity_y,
+        },
+    );
+
+    match distance_to_floor(app) {
+        Ok(Some(distance)) if distance <= DROP_FLOOR_THRESHOLD_PX => {
+            if crate::reaction_cooldown::allow_reaction("drag:window") {
+                let _ = app.emit(
+                    "pet-dropped",
+                    PetDroppedPayload {
+                        distance_to_floor: distance.max(0),
+                        velocity_x,
+                        velocity_y,
+                    },
+                );
+            }
+        }
+        Ok(_) => {}
+        Err(error) => tracing::warn!("failed to measure drop distance: {error}"),
+    }
+
+    if let Err(error) = crate::window_placement::snap_after_drag(app) {
+        tracing::warn!("failed to snap after drag: {error}");
+    }
+}
+
+fn spawn_drag_end_watcher(app: &AppHandle) {
+    let watcher_app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("drag-end-watcher".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(DRAG_END_POLL_INTERVAL);
+            let state = watcher_app.state::<DragState>();
+            let samples = {
+                let Ok(mut tracker) = state.tracker.lock() else {
+                    return;
+                };
+                let idle = tracker
+                    .samples
+                    .back()
+                    .is_none_or(|(at, _)| at.elapsed() >= DRAG_END_IDLE);
+                if !idle {
+                    continue;
+                }
+                tracker.watching = false;
+                tracker.started = false;
+                std::mem::take(&mut tracker.samples)
+            };
+            finish_drag(&watcher_app, samples);
+            return;
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start drag-end watcher: {error}");
+        if let Ok(mut tracker) = app.state::<DragState>().tracker.lock() {
+            tracker.watching = false;
+            tracker.started = false;
+        }
+    }
+}
+
+/// Records a main-window move; called from `WindowEvent::Moved`.
+pub(crate) fn record_window_moved(app: &AppHandle, position: PhysicalPosition<i32>) {
+    let state = app.state::<DragState>();
+    let Ok(mut tracker) = state.tracker.lock() else {
+        return;
+    };
+
+    // Only moves following `start_window_drag` are a drag; everything else is
+    // programmatic (snap_to, nudge_pet, anchoring).
+    if !tracker.started {
+        match tracker.armed_at.take() {
+            Some(armed_at) if armed_at.elapsed() <= DRAG_ARM_TIMEOUT => {}
+            _ => return,
+        }
+        tracker.started = true;
+        let payload = DragStartPayload {
+            source: "window",
+            x: position.x,
+            y: position.y,
+        };
+        let _ = app.emit("drag-start", payload);
+    }
+
+    if tracker.samples.len() == MAX_DRAG_SAMPLES {
+        tracker.samples.pop_front();
+    }
+    tracker.samples.push_back((Instant::now(), position));
+
+    if !tracker.watching {
+        tracker.watching = true;
+        drop(tracker);
+        spawn_drag_end_watcher(app);
+    }
+}
+
+/// Hands the main window to the OS drag loop and marks the moves that follow
+/// as a user drag, so `drag-start` / `drag-end` only fire for real drags.
+#[tauri::command]
+pub fn start_window_drag(app: AppHandle) -> Result<(), String> {
+    if let Ok(mut tracker) = app.state::<DragState>().tracker.lock() {
+        tracker.armed_at = Some(Instant::now());
+    }
+    main_window(&app, "start_window_drag")?
+        .start_dragging()
+        .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(r#type: &str, button: Option<&str>, position: Option<(f64, f64)>) -> GlobalInputEvent {
+        GlobalInputEvent {
+            r#type: r#type.to_string(),
+            key_code: None,
+            button: button.map(str::to_string),
+            x: position.map(|(x, _)| x),
+            y: position.map(|(_, y)| y),
+            distance: None,
+            timestamp: 0,
+        }
+    }
+
+    fn pointer_payload(button: &str, x: f64, y: f64) -> ButtonDragPayload {
+        ButtonDragPayload {
+            source: "pointer",
+            button: button.to_string(),
+            x,
+            y,
+        }
+    }
+
+    #[test]
+    fn middle_button_press_move_release_is_a_drag() {
+        let mut tracker = ButtonDragTracker::default();
+
+        assert!(tracker
+            .apply(&event("MouseMove", None, Some((10.0, 10.0))))
+            .is_empty());
+        assert!(tracker
+            .apply(&event("ButtonPress", Some("Middle"), None))
+            .is_empty());
+        // Jitter inside the threshold keeps it a click.
+        assert!(tracker
+            .apply(&event("MouseMove", None, Some((12.0, 11.0))))
+            .is_empty());
+        assert_eq!(
+            tracker.apply(&event("MouseMove", None, Some((30.0, 10.0)))),
+            vec![ButtonDragEvent::Start(pointer_payload(
+                "Middle", 30.0, 10.0
+            ))]
+        );
+        assert!(tracker
+            .apply(&event("MouseMove", None, Some((50.0, 20.0))))
+            .is_empty());
+        assert_eq!(
+            tracker.apply(&event("ButtonRelease", Some("Middle"), None)),
+            vec![ButtonDragEvent::End(pointer_payload("Middle", 50.0, 20.0))]
+        );
+    }
+
+    #[test]
+    fn middle_click_without_movement_is_not_a_drag() {
+        let mut tracker = ButtonDragTracker::default();
+
+        tracker.apply(&event("MouseMove", None, Some((10.0, 10.0))));
+        assert!(tracker
+            .apply(&event("ButtonPress", Some("Middle"), None))
+            .is_empty());
+        assert!(tracker
+            .apply(&event("ButtonRelease", Some("Middle"), None))
+            .is_empty());
+    }
+
+    #[test]
+    fn overlapping_buttons_are_tracked_separately() {
+        let mut tracker = ButtonDragTracker::default();
+
+        tracker.apply(&event("MouseMove", None, Some((0.0, 0.0))));
+        tracker.apply(&event("ButtonPress", Some("Left"), None));
+        assert_eq!(
+            tracker.apply(&event("MouseMove", None, Some((10.0, 0.0)))),
+            vec![ButtonDragEvent::Start(pointer_payload("Left", 10.0, 0.0))]
+        );
+        tracker.apply(&event("ButtonPress", Some("Middle"), None));
+        assert_eq!(
+            tracker.apply(&event("MouseMove", None, Some((20.0, 0.0)))),
+            vec![ButtonDragEvent::Start(pointer_payload("Middle", 20.0, 0.0))]
+        );
+        assert_eq!(
+            tracker.apply(&event("ButtonRelease", Some("Left"), None)),
+            vec![ButtonDragEvent::End(pointer_payload("Left", 20.0, 0.0))]
+        );
+        assert_eq!(
+            tracker.apply(&event("ButtonRelease", Some("Middle"), None)),
+            vec![ButtonDragEvent::End(pointer_payload("Middle", 20.0, 0.0))]
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use crate::diagnostics::{DiagnosticsConfig, SharedDiagnosticsState};
+use crate::input_listener::{ListenerConfig, SharedInputListenerState};
+use crate::persist::load_value;
+use crate::window_placement::{get_snap_config, SnapConfig};
+use crate::{main_window, UiState, GHOST_OPACITY};
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Manager};
+
+// Frontend-owned pet scale (see `src/lib/settings.ts`).
+const SCALE_KEY: &str = "scale";
+
+/// What the running app is actually using. UI flags come from `UiState`, not
+/// the store, so a mismatch with the persisted settings shows up here.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EffectiveConfig {
+    pub click_through: bool,
+    pub locked: bool,
+    pub snap_enabled: bool,
+    pub do_not_disturb: bool,
+    pub always_on_top: bool,
+    pub ghost_mode: bool,
+    /// A `flash_over_fullscreen` is temporarily forcing the window on top.
+    pub flashing: bool,
+    pub opacity: f64,
+    /// Pet scale as last saved by the renderer.
+    pub scale: Option<f64>,
+    /// DPI scale of the monitor the main window is on.
+    pub scale_factor: Option<f64>,
+    pub snap: SnapConfig,
+    pub listener: ListenerConfig,
+    pub diagnostics: DiagnosticsConfig,
+}
+
+pub(crate) fn effective_config(app: &AppHandle) -> EffectiveConfig {
+    let state = app.state::<UiState>();
+    let ghost_mode = state.ghost_mode.load(Ordering::SeqCst);
+
+    EffectiveConfig {
+        click_through: state.click_through.load(Ordering::SeqCst),
+        locked: state.locked.load(Ordering::SeqCst),
+        snap_enabled: state.snap_enabled.load(Ordering::SeqCst),
+        do_not_disturb: state.do_not_disturb.load(Ordering::SeqCst),
+        always_on_top: state.always_on_top.load(Ordering::SeqCst),
+        ghost_mode,
+        flashing: crate::flash::is_flashing(),
+        opacity: if ghost_mode { GHOST_OPACITY } else { 1.0 },
+        scale: load_value(app, SCALE_KEY),
+        scale_factor: main_window(app, "get_effective_config")
+            .and_then(|window| window.scale_factor().map_err(|error| error.to_string()))
+            .ok(),
+        snap: get_snap_config(app.clone(), app.state::<UiState>()),
+        listener: app.state::<SharedInputListenerState>().config(),
+        diagnostics: app.state::<SharedDiagnosticsState>().config(),
+    }
+}
+
+#[tauri::command]
+pub fn get_effective_config(app: AppHandle) -> EffectiveConfig {
+    effective_config(&app)
+}
@@ -0,0 +1,102 @@
+use crate::{main_window, UiState};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const MAX_FLASH_DURATION_MS: u64 = 60_000;
+
+/// Bumped per flash so only the newest one's timer restores the window.
+static FLASH_TOKEN: AtomicU64 = AtomicU64::new(0);
+/// Window state from before the first of any overlapping flashes.
+static FLASH_RESTORE: Mutex<Option<FlashRestore>> = Mutex::new(None);
+
+#[derive(Clone, Copy)]
+struct FlashRestore {
+    visible: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlashPayload {
+    duration_ms: u64,
+}
+
+/// True while a flash owns the window's visibility. Anything that hides the pet
+/// automatically (e.g. for fullscreen apps) should leave it alone until then.
+pub(crate) fn is_flashing() -> bool {
+    FLASH_RESTORE
+        .lock()
+        .map(|restore| restore.is_some())
+        .unwrap_or(false)
+}
+
+fn end_flash(app: &AppHandle, token: u64, duration_ms: u64) -> Result<(), String> {
+    let restore = {
+        let mut restore = FLASH_RESTORE
+            .lock()
+            .map_err(|_| "flash state unavailable".to_string())?;
+        if FLASH_TOKEN.load(Ordering::SeqCst) != token {
+            return Ok(());
+        }
+        restore.take()
+    };
+    let Some(restore) = restore else {
+        return Ok(());
+    };
+
+    let window = main_window(app, "flash_over_fullscreen")?;
+    let always_on_top = app.state::<UiState>().always_on_top.load(Ordering::SeqCst);
+    window
+        .set_always_on_top(always_on_top)
+        .map_err(|error| error.to_string())?;
+    if !restore.visible {
+        window.hide().map_err(|error| error.to_string())?;
+    }
+    let _ = app.emit("flash-end", FlashPayload { duration_ms });
+    Ok(())
+}
+
+/// Shows the pet on top of everything, including fullscreen apps, for
+/// `duration_ms`, then puts visibility and always-on-top back as they were.
+/// A flash during a flash extends it rather than stacking.
+#[tauri::command]
+pub fn flash_over_fullscreen(app: AppHandle, duration_ms: u64) -> Result<(), String> {
+    let duration_ms = duration_ms.clamp(1, MAX_FLASH_DURATION_MS);
+    let window = main_window(&app, "flash_over_fullscreen")?;
+
+    let token = {
+        let mut restore = FLASH_RESTORE
+            .lock()
+            .map_err(|_| "flash state unavailable".to_string())?;
+        if restore.is_none() {
+            *restore = Some(FlashRestore {
+                visible: window.is_visible().map_err(|error| error.to_string())?,
+            });
+        }
+        FLASH_TOKEN.fetch_add(1, Ordering::SeqCst) + 1
+    };
+
+    window
+        .set_always_on_top(true)
+        .map_err(|error| error.to_string())?;
+    window.show().map_err(|error| error.to_string())?;
+    let _ = app.emit("flash-start", FlashPayload { duration_ms });
+
+    let timer_app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("flash-timer".to_string())
+        .spawn(move || {
+            std::thread::sleep(Duration::from_millis(duration_ms));
+            if let Err(error) = end_flash(&timer_app, token, duration_ms) {
+                tracing::warn!("failed to end flash: {error}");
+            }
+        });
+
+    if let Err(error) = spawned {
+        end_flash(&app, token, duration_ms)?;
+        return Err(format!("failed to start flash timer: {error}"));
+    }
+    Ok(())
+}
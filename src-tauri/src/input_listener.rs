@@ -1,29 +1,473 @@
-use crate::diagnostics::{GlobalInputEvent, SharedDiagnosticsState};
+use crate::diagnostics::{now_timestamp_ms, GlobalInputEvent, SharedDiagnosticsState};
+use crate::drag::ButtonDragTracker;
+use crate::input_session::SessionRecorder;
+use crate::permissions::{refresh_permissions, PermissionState};
+use crate::persist::{load_value, save_value};
 use crossbeam_channel::{bounded, Receiver, Sender, TrySendError};
 use rdev::{Button, Event, EventType, Key};
 use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::sync::{
-    atomic::{AtomicBool, AtomicU64, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, AtomicU8, AtomicUsize, Ordering},
+    Arc, Mutex,
 };
 use std::time::{Duration, Instant, UNIX_EPOCH};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
-const INPUT_CHANNEL_CAPACITY: usize = 512;
+const CAPTURE_SCOPE_KEY: &str = "captureScope";
+const IDLE_THRESHOLD_KEY: &str = "idleThresholdMs";
+const CHANNEL_CAPACITY_KEY: &str = "inputChannelCapacity";
+const DEFAULT_IDLE_THRESHOLD_MS: u64 = 5 * 60 * 1_000;
+// Shorter thresholds flip between idle and active on every pause in typing.
+const MIN_IDLE_THRESHOLD_MS: u64 = 10_000;
+const DEFAULT_INPUT_CHANNEL_CAPACITY: usize = 512;
+const MIN_INPUT_CHANNEL_CAPACITY: usize = 16;
+const MAX_INPUT_CHANNEL_CAPACITY: usize = 16_384;
 const MOUSE_MOVE_THROTTLE_MS: u64 = 16;
-const FORWARDER_POLL_MS: u64 = 4;
-const FORWARDER_IDLE_POLL_MS: u64 = 80;
+const DEFAULT_FORWARDER_POLL_MS: u64 = 4;
+const DEFAULT_FORWARDER_IDLE_POLL_MS: u64 = 80;
+// Below these the forwarder thread effectively busy-spins on `recv_timeout`.
+const MIN_FORWARDER_POLL_MS: u64 = 1;
+const MIN_FORWARDER_IDLE_POLL_MS: u64 = 10;
+const MAX_FORWARDER_POLL_MS: u64 = 1_000;
+// Longer batches make clicks and key presses feel late in the renderer.
+const MAX_INPUT_BATCH_MS: u64 = 250;
+// A forwarder that has not looped for this long past its poll interval is stuck,
+// most likely blocked inside an emit.
+const FORWARDER_STALL_GRACE_MS: u64 = 5_000;
+// Releases can be missed (focus changes, secure input), so stale held state is
+// dropped after this long without any press or release.
+const HELD_INPUT_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+const SELF_TEST_WINDOW: Duration = Duration::from_secs(5);
+const SELF_TEST_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Off while the renderer reloads: input is still processed and recorded in
+/// diagnostics, only the events to the frontend are skipped.
+static FRONTEND_EMIT: AtomicBool = AtomicBool::new(true);
+/// Logs every forwarded event at `trace`; see `set_event_trace`.
+static EVENT_TRACE: AtomicBool = AtomicBool::new(false);
+/// Bumped per `set_event_trace` so an older auto-disable timer becomes a no-op.
+static EVENT_TRACE_TOKEN: AtomicU64 = AtomicU64::new(0);
 
-#[derive(Default)]
 pub struct InputListenerState {
     running: AtomicBool,
     forwarding: AtomicBool,
     health_token: AtomicU64,
     events_seen_since_start: AtomicU64,
+    /// Read when the channel is created, so changes apply on the next listener start.
+    channel_capacity: AtomicUsize,
+    /// Event type -> minimum interval between emits. Types without an entry
+    /// are forwarded immediately.
+    throttle_intervals: Mutex<HashMap<String, u64>>,
+    /// Stretch the `MouseMove` interval to the renderer's reported frame time.
+    adaptive_throttle: AtomicBool,
+    /// Do-not-disturb: keep listening (and health-checking) but drop events.
+    suppressed: AtomicBool,
+    /// A `CaptureScope`; events outside it are dropped in the rdev callback.
+    capture_scope: AtomicU8,
+    /// `recv_timeout` used by the forwarder while forwarding / while stopped.
+    forwarder_poll_ms: AtomicU64,
+    forwarder_idle_poll_ms: AtomicU64,
+    /// Frame batching of unthrottled events; 0 emits each one on its own.
+    batch_interval_ms: AtomicU64,
+    /// While batching, also emit every event as `global-input`.
+    batch_keeps_per_event: AtomicBool,
+    held_inputs: Mutex<HeldInputTracker>,
+    button_drags: Mutex<ButtonDragTracker>,
+    /// Handles onto the forwarder channel, kept so replayed events can join it.
+    injector: Mutex<Option<(Sender<GlobalInputEvent>, Receiver<GlobalInputEvent>)>>,
+    session_recorder: Mutex<Option<SessionRecorder>>,
+    /// Thread liveness for `get_thread_health`; timestamps are Unix ms, 0 = never.
+    forwarder_alive: AtomicBool,
+    forwarder_last_tick_ms: AtomicU64,
+    listener_last_event_ms: AtomicU64,
+    health_checks_pending: AtomicUsize,
+    health_check_started_ms: AtomicU64,
+    /// No input for this long marks the user idle; read on every forwarder tick.
+    idle_threshold_ms: AtomicU64,
+    input_idle: AtomicBool,
+}
+
+impl Default for InputListenerState {
+    fn default() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+            forwarding: AtomicBool::new(false),
+            health_token: AtomicU64::new(0),
+            events_seen_since_start: AtomicU64::new(0),
+            channel_capacity: AtomicUsize::new(DEFAULT_INPUT_CHANNEL_CAPACITY),
+            throttle_intervals: Mutex::new(HashMap::from([(
+                "MouseMove".to_string(),
+                MOUSE_MOVE_THROTTLE_MS,
+            )])),
+            adaptive_throttle: AtomicBool::new(false),
+            suppressed: AtomicBool::new(false),
+            capture_scope: AtomicU8::new(CaptureScope::Full as u8),
+            forwarder_poll_ms: AtomicU64::new(DEFAULT_FORWARDER_POLL_MS),
+            forwarder_idle_poll_ms: AtomicU64::new(DEFAULT_FORWARDER_IDLE_POLL_MS),
+            batch_interval_ms: AtomicU64::new(0),
+            batch_keeps_per_event: AtomicBool::new(false),
+            held_inputs: Mutex::new(HeldInputTracker::default()),
+            button_drags: Mutex::new(ButtonDragTracker::default()),
+            injector: Mutex::new(None),
+            session_recorder: Mutex::new(None),
+            forwarder_alive: AtomicBool::new(false),
+            forwarder_last_tick_ms: AtomicU64::new(0),
+            listener_last_event_ms: AtomicU64::new(0),
+            health_checks_pending: AtomicUsize::new(0),
+            health_check_started_ms: AtomicU64::new(0),
+            idle_threshold_ms: AtomicU64::new(DEFAULT_IDLE_THRESHOLD_MS),
+            input_idle: AtomicBool::new(false),
+        }
+    }
+}
+
+/// Which input categories the listener captures at all. Unlike frontend
+/// filtering, events outside the scope never leave the listener thread.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CaptureScope {
+    Full = 0,
+    /// Buttons, pointer movement and the wheel; no keystrokes.
+    MouseOnly = 1,
+    KeyboardOnly = 2,
+}
+
+impl CaptureScope {
+    pub fn parse(scope: &str) -> Result<Self, String> {
+        match scope {
+            "full" => Ok(Self::Full),
+            "mouse_only" => Ok(Self::MouseOnly),
+            "keyboard_only" => Ok(Self::KeyboardOnly),
+            _ => Err(format!(
+                "unknown capture scope {scope}; expected full, mouse_only or keyboard_only"
+            )),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Full => "full",
+            Self::MouseOnly => "mouse_only",
+            Self::KeyboardOnly => "keyboard_only",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::MouseOnly,
+            2 => Self::KeyboardOnly,
+            _ => Self::Full,
+        }
+    }
+
+    fn allows(self, event_type: &EventType) -> bool {
+        let keyboard = matches!(
+            event_type,
+            EventType::KeyPress(_) | EventType::KeyRelease(_)
+        );
+        match self {
+            Self::Full => true,
+            Self::MouseOnly => !keyboard,
+            Self::KeyboardOnly => keyboard,
+        }
+    }
+}
+
+/// Live listener settings, for `get_effective_config`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenerConfig {
+    pub running: bool,
+    pub forwarding: bool,
+    pub suppressed: bool,
+    pub capture_scope: &'static str,
+    pub channel_capacity: usize,
+    pub throttle_ms: BTreeMap<String, u64>,
+    pub adaptive_throttle: bool,
+    pub forwarder_poll: ForwarderPollTimings,
+    pub batching: InputBatching,
+    pub frontend_emit: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThrottleChangedPayload {
+    r#type: String,
+    /// 0 when the type is no longer throttled.
+    ms: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AdaptiveThrottleChangedPayload {
+    enabled: bool,
+}
+
+fn emit_setting_changed<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(error) = app.emit(event, payload) {
+        tracing::warn!("failed to emit {event} event: {error}");
+    }
+}
+
+/// Emits `throttle-changed`, `forwarder-poll-changed` and
+/// `adaptive-throttle-changed` for whatever differs between `before` and
+/// `after`, so single setters and presets report changes the same way.
+pub(crate) fn emit_tuning_changes(
+    app: &AppHandle,
+    before: &ListenerConfig,
+    after: &ListenerConfig,
+) {
+    let types: BTreeSet<&String> = before
+        .throttle_ms
+        .keys()
+        .chain(after.throttle_ms.keys())
+        .collect();
+    for r#type in types {
+        let ms = after.throttle_ms.get(r#type).copied();
+        if before.throttle_ms.get(r#type).copied() != ms {
+            let payload = ThrottleChangedPayload {
+                r#type: r#type.clone(),
+                ms: ms.unwrap_or(0),
+            };
+            emit_setting_changed(app, "throttle-changed", payload);
+        }
+    }
+    if before.forwarder_poll != after.forwarder_poll {
+        emit_setting_changed(app, "forwarder-poll-changed", after.forwarder_poll);
+    }
+    if before.adaptive_throttle != after.adaptive_throttle {
+        let payload = AdaptiveThrottleChangedPayload {
+            enabled: after.adaptive_throttle,
+        };
+        emit_setting_changed(app, "adaptive-throttle-changed", payload);
+    }
+}
+
+impl InputListenerState {
+    pub(crate) fn config(&self) -> ListenerConfig {
+        ListenerConfig {
+            running: self.running.load(Ordering::SeqCst),
+            forwarding: self.forwarding.load(Ordering::SeqCst),
+            suppressed: self.suppressed.load(Ordering::SeqCst),
+            capture_scope: self.capture_scope().as_str(),
+            channel_capacity: self.channel_capacity.load(Ordering::SeqCst),
+            throttle_ms: self
+                .throttle_intervals
+                .lock()
+                .map(|intervals| intervals.clone().into_iter().collect())
+                .unwrap_or_default(),
+            adaptive_throttle: self.adaptive_throttle.load(Ordering::SeqCst),
+            forwarder_poll: forwarder_poll_timings(self),
+            batching: input_batching(self),
+            frontend_emit: FRONTEND_EMIT.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Puts throttling and forwarder polling back to their startup defaults.
+    pub(crate) fn reset_tuning(&self) -> Result<(), String> {
+        let defaults = Self::default();
+        let mut intervals = self
+            .throttle_intervals
+            .lock()
+            .map_err(|_| "throttle state unavailable".to_string())?;
+        *intervals = defaults.throttle_intervals.into_inner().unwrap_or_default();
+        self.forwarder_poll_ms
+            .store(defaults.forwarder_poll_ms.into_inner(), Ordering::SeqCst);
+        self.forwarder_idle_poll_ms.store(
+            defaults.forwarder_idle_poll_ms.into_inner(),
+            Ordering::SeqCst,
+        );
+        self.adaptive_throttle
+            .store(defaults.adaptive_throttle.into_inner(), Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Applies a tuning preset's listener settings under the throttle lock, so a
+    /// concurrent `set_throttle_for` cannot interleave with it. Poll values are
+    /// clamped like `set_forwarder_poll_ms`.
+    pub(crate) fn apply_tuning(
+        &self,
+        mouse_move_throttle_ms: u64,
+        poll: ForwarderPollTimings,
+        adaptive_throttle: bool,
+    ) -> Result<(), String> {
+        let mut intervals = self
+            .throttle_intervals
+            .lock()
+            .map_err(|_| "throttle state unavailable".to_string())?;
+        intervals.insert("MouseMove".to_string(), mouse_move_throttle_ms);
+        self.forwarder_poll_ms.store(
+            poll.active_ms
+                .clamp(MIN_FORWARDER_POLL_MS, MAX_FORWARDER_POLL_MS),
+            Ordering::SeqCst,
+        );
+        self.forwarder_idle_poll_ms.store(
+            poll.idle_ms
+                .clamp(MIN_FORWARDER_IDLE_POLL_MS, MAX_FORWARDER_POLL_MS),
+            Ordering::SeqCst,
+        );
+        self.adaptive_throttle
+            .store(adaptive_throttle, Ordering::SeqCst);
+        Ok(())
+    }
+
+    pub(crate) fn idle_threshold_ms(&self) -> u64 {
+        self.idle_threshold_ms.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn capture_scope(&self) -> CaptureScope {
+        CaptureScope::from_u8(self.capture_scope.load(Ordering::Relaxed))
+    }
+
+    pub fn set_suppressed(&self, suppressed: bool) {
+        self.suppressed.store(suppressed, Ordering::SeqCst);
+    }
+
+    /// Swaps the active session recorder, returning the one it replaces.
+    pub(crate) fn replace_session_recorder(
+        &self,
+        recorder: Option<SessionRecorder>,
+    ) -> Option<SessionRecorder> {
+        let mut current = self
+            .session_recorder
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        std::mem::replace(&mut *current, recorder)
+    }
+
+    /// Appends to the active session, if any, under the same recording switch
+    /// and anonymization level as the diagnostics buffer.
+    fn record_session_event(
+        &self,
+        diagnostics: &SharedDiagnosticsState,
+        payload: &GlobalInputEvent,
+    ) {
+        if !diagnostics.input_recording() {
+            return;
+        }
+        if let Ok(mut recorder) = self.session_recorder.lock() {
+            if let Some(recorder) = recorder.as_mut() {
+                recorder.record(&diagnostics.input_anonymization().apply(payload.clone()));
+            }
+        }
+    }
+
+    /// Queues a synthetic event as if the OS hook had delivered it.
+    pub(crate) fn inject_event(&self, payload: GlobalInputEvent) -> Result<(), String> {
+        if !self.forwarding.load(Ordering::SeqCst) {
+            return Err("input listener is not running".to_string());
+        }
+        if self.suppressed.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let injector = self
+            .injector
+            .lock()
+            .map_err(|_| "input channel unavailable".to_string())?;
+        let (sender, receiver) = injector
+            .as_ref()
+            .ok_or_else(|| "input listener is not running".to_string())?;
+        enqueue_with_drop_old(sender, receiver, payload);
+        Ok(())
+    }
+
+    fn throttle_interval(&self, event_type: &str) -> Option<Duration> {
+        let intervals = self.throttle_intervals.lock().ok()?;
+        intervals
+            .get(event_type)
+            .copied()
+            .map(Duration::from_millis)
+    }
+
+    fn effective_throttle_interval(
+        &self,
+        event_type: &str,
+        diagnostics: &SharedDiagnosticsState,
+    ) -> Duration {
+        let interval = self.throttle_interval(event_type).unwrap_or(Duration::ZERO);
+        if event_type != "MouseMove" || !self.adaptive_throttle.load(Ordering::Relaxed) {
+            return interval;
+        }
+
+        match diagnostics.fps().filter(|fps| *fps >= 1.0) {
+            Some(fps) => interval.max(Duration::from_secs_f64(1.0 / fps)),
+            None => interval,
+        }
+    }
 }
 
 pub type SharedInputListenerState = Arc<InputListenerState>;
 
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldInputs {
+    pub keys: Vec<String>,
+    pub buttons: Vec<String>,
+}
+
+#[derive(Default)]
+struct HeldInputTracker {
+    keys: BTreeSet<String>,
+    buttons: BTreeSet<String>,
+    last_change: Option<Instant>,
+}
+
+impl HeldInputTracker {
+    fn snapshot(&self) -> HeldInputs {
+        HeldInputs {
+            keys: self.keys.iter().cloned().collect(),
+            buttons: self.buttons.iter().cloned().collect(),
+        }
+    }
+
+    /// Applies a press/release and returns the new set if it changed.
+    fn apply(&mut self, payload: &GlobalInputEvent) -> Option<HeldInputs> {
+        let changed = match (payload.r#type.as_str(), &payload.key_code, &payload.button) {
+            ("KeyPress", Some(key), _) => self.keys.insert(key.clone()),
+            ("KeyRelease", Some(key), _) => self.keys.remove(key),
+            ("ButtonPress", _, Some(button)) => self.buttons.insert(button.clone()),
+            ("ButtonRelease", _, Some(button)) => self.buttons.remove(button),
+            _ => return None,
+        };
+        self.last_change = Some(Instant::now());
+        changed.then(|| self.snapshot())
+    }
+
+    fn clear_if_idle(&mut self) -> Option<HeldInputs> {
+        if self.keys.is_empty() && self.buttons.is_empty() {
+            return None;
+        }
+        if self
+            .last_change
+            .is_some_and(|at| at.elapsed() < HELD_INPUT_IDLE_TIMEOUT)
+        {
+            return None;
+        }
+        self.keys.clear();
+        self.buttons.clear();
+        Some(self.snapshot())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwarderPollTimings {
+    pub active_ms: u64,
+    pub idle_ms: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputSelfTest {
+    pub listener_started: bool,
+    /// Events observed during the test window, including ones dropped by do-not-disturb.
+    pub events_seen: u64,
+    pub permission: PermissionState,
+    pub platform: String,
+    pub suggestion: String,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct InputHealthPayload {
@@ -33,47 +477,217 @@ struct InputHealthPayload {
     platform: String,
 }
 
+/// `listener-exited`: the rdev hook is gone and `start_listener` must be called
+/// again. `reason` is `"error"` when rdev failed, `"stopped"` when it returned.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListenerExitedPayload {
+    reason: &'static str,
+    platform: String,
+    events_seen: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
 fn platform_name() -> String {
     std::env::consts::OS.to_string()
 }
 
-fn emit_input_health(app: &AppHandle, payload: InputHealthPayload) {
-    if let Err(err) = app.emit("input-health", payload) {
-        tracing::warn!("failed to emit input-health event: {err}");
+/// Emits an input-related event unless frontend emission is paused.
+pub(crate) fn emit_to_frontend<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if !FRONTEND_EMIT.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Err(err) = app.emit(event, payload) {
+        tracing::warn!("failed to emit {event} event: {err}");
     }
 }
 
+fn emit_input_health(app: &AppHandle, payload: InputHealthPayload) {
+    emit_to_frontend(app, "input-health", payload);
+}
+
+fn emit_held_inputs(app: &AppHandle, held: Option<HeldInputs>) {
+    let Some(held) = held else {
+        return;
+    };
+    emit_to_frontend(app, "held-inputs-changed", held);
+}
+
 fn emit_global_input(
     app: &AppHandle,
     diagnostics: &SharedDiagnosticsState,
     payload: GlobalInputEvent,
 ) {
-    diagnostics.record_input_event(payload.clone());
+    if diagnostics.input_recording() {
+        diagnostics.record_input_event(payload.clone());
+    }
+
+    trace_event(&payload);
+    emit_to_frontend(app, "global-input", payload);
+}
 
-    if let Err(err) = app.emit("global-input", payload) {
-        tracing::warn!("failed to emit global-input event: {err}");
+fn trace_event(payload: &GlobalInputEvent) {
+    if EVENT_TRACE.load(Ordering::Relaxed) {
+        tracing::trace!("forwarding {payload:?}");
     }
 }
 
-fn maybe_emit_pending_mouse_move(
-    app: &AppHandle,
-    diagnostics: &SharedDiagnosticsState,
-    pending_mouse_move: &mut Option<GlobalInputEvent>,
-    last_mouse_emit: &mut Instant,
-    force: bool,
-) {
-    if pending_mouse_move.is_none() {
-        return;
+/// Latest-wins buffer for one throttled event type. Positional events also
+/// accumulate the path length travelled between emits.
+#[derive(Default)]
+struct ThrottleSlot {
+    pending: Option<GlobalInputEvent>,
+    last_emit: Option<Instant>,
+    last_position: Option<(f64, f64)>,
+    distance: f64,
+}
+
+impl ThrottleSlot {
+    fn push(&mut self, payload: GlobalInputEvent) {
+        if let (Some(x), Some(y)) = (payload.x, payload.y) {
+            if let Some((last_x, last_y)) = self.last_position {
+                self.distance += (x - last_x).hypot(y - last_y);
+            }
+            self.last_position = Some((x, y));
+        }
+        self.pending = Some(payload);
     }
 
-    if !force && last_mouse_emit.elapsed() < Duration::from_millis(MOUSE_MOVE_THROTTLE_MS) {
-        return;
+    fn take_if_due(&mut self, interval: Duration, force: bool) -> Option<GlobalInputEvent> {
+        let due = force
+            || self
+                .last_emit
+                .is_none_or(|last_emit| last_emit.elapsed() >= interval);
+        if !due {
+            return None;
+        }
+
+        let mut payload = self.pending.take()?;
+        if payload.x.is_some() && payload.y.is_some() {
+            payload.distance = Some(self.distance);
+        }
+        self.distance = 0.0;
+        self.last_emit = Some(Instant::now());
+        Some(payload)
     }
+}
+
+/// Drops a key event that exactly repeats the one before it (same type, key
+/// and millisecond timestamp), as some keyboards and remote-desktop clients
+/// deliver. Auto-repeat and fast typing differ in timestamp, so they pass.
+#[derive(Default)]
+struct KeyDeduplicator {
+    last: Option<(String, Option<String>, u64)>,
+}
+
+impl KeyDeduplicator {
+    fn is_duplicate(&mut self, payload: &GlobalInputEvent) -> bool {
+        if !matches!(payload.r#type.as_str(), "KeyPress" | "KeyRelease") {
+            self.last = None;
+            return false;
+        }
+
+        let current = (
+            payload.r#type.clone(),
+            payload.key_code.clone(),
+            payload.timestamp,
+        );
+        if self.last.as_ref() == Some(&current) {
+            return true;
+        }
+        self.last = Some(current);
+        false
+    }
+}
 
-    if let Some(payload) = pending_mouse_move.take() {
+#[derive(Default)]
+struct EventThrottler {
+    slots: HashMap<String, ThrottleSlot>,
+}
+
+impl EventThrottler {
+    fn push(&mut self, payload: GlobalInputEvent) {
+        self.slots
+            .entry(payload.r#type.clone())
+            .or_default()
+            .push(payload);
+    }
+
+    fn flush_due(
+        &mut self,
+        app: &AppHandle,
+        diagnostics: &SharedDiagnosticsState,
+        listener_state: &InputListenerState,
+        force: bool,
+    ) {
+        for (event_type, slot) in &mut self.slots {
+            if slot.pending.is_none() {
+                continue;
+            }
+            let interval = listener_state.effective_throttle_interval(event_type, diagnostics);
+            if let Some(payload) = slot.take_if_due(interval, force) {
+                emit_global_input(app, diagnostics, payload);
+            }
+        }
+    }
+}
+
+/// Holds unthrottled events for up to one batch interval, so a burst of
+/// clicks or keys crosses IPC as one `global-input-batch` event.
+#[derive(Default)]
+struct FrameBatcher {
+    events: Vec<GlobalInputEvent>,
+    opened: Option<Instant>,
+}
+
+impl FrameBatcher {
+    fn push(&mut self, payload: GlobalInputEvent) {
+        self.opened.get_or_insert_with(Instant::now);
+        self.events.push(payload);
+    }
+
+    fn take_if_due(&mut self, interval: Duration, force: bool) -> Option<Vec<GlobalInputEvent>> {
+        let due = force
+            || self
+                .opened
+                .is_some_and(|opened| opened.elapsed() >= interval);
+        if !due || self.events.is_empty() {
+            return None;
+        }
+        self.opened = None;
+        Some(std::mem::take(&mut self.events))
+    }
+
+    fn flush_due(&mut self, app: &AppHandle, listener_state: &InputListenerState, force: bool) {
+        let interval = listener_state.batch_interval_ms.load(Ordering::Relaxed);
+        if let Some(batch) = self.take_if_due(Duration::from_millis(interval), force) {
+            batch.iter().for_each(trace_event);
+            emit_to_frontend(app, "global-input-batch", batch);
+        }
+    }
+}
+
+/// Emits an unthrottled event now, or queues it in the current batch when
+/// batching is on. With `per_event` each event goes out on its own instead, so
+/// consumers never see it twice.
+fn forward_unthrottled(
+    app: &AppHandle,
+    diagnostics: &SharedDiagnosticsState,
+    listener_state: &InputListenerState,
+    batcher: &mut FrameBatcher,
+    payload: GlobalInputEvent,
+) {
+    if listener_state.batch_interval_ms.load(Ordering::Relaxed) == 0
+        || listener_state.batch_keeps_per_event.load(Ordering::Relaxed)
+    {
         emit_global_input(app, diagnostics, payload);
-        *last_mouse_emit = Instant::now();
+        return;
     }
+    if diagnostics.input_recording() {
+        diagnostics.record_input_event(payload.clone());
+    }
+    batcher.push(payload);
 }
 
 fn enqueue_with_drop_old(
@@ -96,55 +710,105 @@ fn enqueue_with_drop_old(
     }
 }
 
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InputIdlePayload {
+    idle: bool,
+    idle_ms: u64,
+}
+
+/// Emits `input-idle-changed` when the time since the last input crosses the
+/// idle threshold in either direction. `since_ms` stands in for the last input
+/// until the listener has seen one.
+fn update_input_idle(app: &AppHandle, state: &InputListenerState, since_ms: u64) {
+    let last_input_ms = state
+        .listener_last_event_ms
+        .load(Ordering::Relaxed)
+        .max(since_ms);
+    let idle_ms = now_timestamp_ms().saturating_sub(last_input_ms);
+    let idle = idle_ms >= state.idle_threshold_ms.load(Ordering::Relaxed);
+    if state.input_idle.swap(idle, Ordering::SeqCst) != idle {
+        emit_to_frontend(
+            app,
+            "input-idle-changed",
+            InputIdlePayload { idle, idle_ms },
+        );
+    }
+}
+
 fn forward_events_loop(
     app: AppHandle,
     listener_state: SharedInputListenerState,
     diagnostics: SharedDiagnosticsState,
     receiver: Receiver<GlobalInputEvent>,
 ) {
-    let mut pending_mouse_move: Option<GlobalInputEvent> = None;
-    let mut last_mouse_emit = Instant::now()
-        .checked_sub(Duration::from_millis(MOUSE_MOVE_THROTTLE_MS))
-        .unwrap_or_else(Instant::now);
+    let mut throttler = EventThrottler::default();
+    let mut deduplicator = KeyDeduplicator::default();
+    let mut batcher = FrameBatcher::default();
+    listener_state.forwarder_alive.store(true, Ordering::SeqCst);
+    listener_state.input_idle.store(false, Ordering::SeqCst);
+    let started_ms = now_timestamp_ms();
 
     while listener_state.running.load(Ordering::Relaxed) || !receiver.is_empty() {
+        listener_state
+            .forwarder_last_tick_ms
+            .store(now_timestamp_ms(), Ordering::Relaxed);
+        update_input_idle(&app, &listener_state, started_ms);
         let poll_ms = if listener_state.forwarding.load(Ordering::Relaxed) {
-            FORWARDER_POLL_MS
+            listener_state.forwarder_poll_ms.load(Ordering::Relaxed)
         } else {
-            FORWARDER_IDLE_POLL_MS
+            listener_state
+                .forwarder_idle_poll_ms
+                .load(Ordering::Relaxed)
         };
 
         match receiver.recv_timeout(Duration::from_millis(poll_ms)) {
             Ok(payload) => {
-                if payload.r#type == "MouseMove" {
-                    pending_mouse_move = Some(payload);
-                    maybe_emit_pending_mouse_move(
-                        &app,
-                        &diagnostics,
-                        &mut pending_mouse_move,
-                        &mut last_mouse_emit,
-                        false,
-                    );
+                if deduplicator.is_duplicate(&payload) {
+                    tracing::debug!("dropping duplicate {} event", payload.r#type);
                     continue;
                 }
+                // Here rather than in the OS hook, so file writes never stall it.
+                listener_state.record_session_event(&diagnostics, &payload);
 
-                maybe_emit_pending_mouse_move(
-                    &app,
-                    &diagnostics,
-                    &mut pending_mouse_move,
-                    &mut last_mouse_emit,
-                    false,
-                );
-                emit_global_input(&app, &diagnostics, payload);
+                let held = listener_state
+                    .held_inputs
+                    .lock()
+                    .ok()
+                    .and_then(|mut tracker| {
+                        tracker.apply(&payload).or_else(|| tracker.clear_if_idle())
+                    });
+                emit_held_inputs(&app, held);
+
+                // Before throttling, so drag thresholds see every pointer move.
+                let drags = listener_state
+                    .button_drags
+                    .lock()
+                    .map(|mut tracker| tracker.apply(&payload))
+                    .unwrap_or_default();
+                for drag in drags {
+                    drag.emit(&app);
+                }
+
+                if listener_state.throttle_interval(&payload.r#type).is_some() {
+                    throttler.push(payload);
+                    throttler.flush_due(&app, &diagnostics, &listener_state, false);
+                    continue;
+                }
+
+                throttler.flush_due(&app, &diagnostics, &listener_state, false);
+                forward_unthrottled(&app, &diagnostics, &listener_state, &mut batcher, payload);
+                batcher.flush_due(&app, &listener_state, false);
             }
             Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
-                maybe_emit_pending_mouse_move(
-                    &app,
-                    &diagnostics,
-                    &mut pending_mouse_move,
-                    &mut last_mouse_emit,
-                    false,
-                );
+                throttler.flush_due(&app, &diagnostics, &listener_state, false);
+                batcher.flush_due(&app, &listener_state, false);
+                let held = listener_state
+                    .held_inputs
+                    .lock()
+                    .ok()
+                    .and_then(|mut tracker| tracker.clear_if_idle());
+                emit_held_inputs(&app, held);
             }
             Err(crossbeam_channel::RecvTimeoutError::Disconnected) => {
                 break;
@@ -152,47 +816,60 @@ fn forward_events_loop(
         }
     }
 
-    maybe_emit_pending_mouse_move(
-        &app,
-        &diagnostics,
-        &mut pending_mouse_move,
-        &mut last_mouse_emit,
-        true,
+    throttler.flush_due(&app, &diagnostics, &listener_state, true);
+    batcher.flush_due(&app, &listener_state, true);
+    listener_state
+        .forwarder_alive
+        .store(false, Ordering::SeqCst);
+}
+
+fn run_health_check(app: &AppHandle, state: &InputListenerState, token: u64) {
+    std::thread::sleep(Duration::from_secs(3));
+
+    if state.health_token.load(Ordering::SeqCst) != token {
+        return;
+    }
+
+    let events_seen = state.events_seen_since_start.load(Ordering::SeqCst);
+    if events_seen == 0 {
+        emit_input_health(
+            app,
+            InputHealthPayload {
+                ok: false,
+                reason: Some("no_events_detected".to_string()),
+                platform: platform_name(),
+            },
+        );
+        return;
+    }
+
+    emit_input_health(
+        app,
+        InputHealthPayload {
+            ok: true,
+            reason: None,
+            platform: platform_name(),
+        },
     );
 }
 
 fn spawn_health_check(app: AppHandle, state: SharedInputListenerState, token: u64) {
-    let _ = std::thread::Builder::new()
+    state.health_checks_pending.fetch_add(1, Ordering::SeqCst);
+    state
+        .health_check_started_ms
+        .store(now_timestamp_ms(), Ordering::SeqCst);
+    let state_for_thread = Arc::clone(&state);
+    let spawned = std::thread::Builder::new()
         .name("global-input-health-check".to_string())
         .spawn(move || {
-            std::thread::sleep(Duration::from_secs(3));
-
-            if state.health_token.load(Ordering::SeqCst) != token {
-                return;
-            }
-
-            let events_seen = state.events_seen_since_start.load(Ordering::SeqCst);
-            if events_seen == 0 {
-                emit_input_health(
-                    &app,
-                    InputHealthPayload {
-                        ok: false,
-                        reason: Some("no_events_detected".to_string()),
-                        platform: platform_name(),
-                    },
-                );
-                return;
-            }
-
-            emit_input_health(
-                &app,
-                InputHealthPayload {
-                    ok: true,
-                    reason: None,
-                    platform: platform_name(),
-                },
-            );
+            run_health_check(&app, &state_for_thread, token);
+            state_for_thread
+                .health_checks_pending
+                .fetch_sub(1, Ordering::SeqCst);
         });
+    if spawned.is_err() {
+        state.health_checks_pending.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 #[tauri::command]
@@ -216,8 +893,12 @@ pub fn start_listener(
     let listener_state = Arc::clone(state.inner());
     let diagnostics_state = Arc::clone(diagnostics.inner());
 
-    let (sender, receiver) = bounded::<GlobalInputEvent>(INPUT_CHANNEL_CAPACITY);
+    let capacity = state.channel_capacity.load(Ordering::SeqCst);
+    let (sender, receiver) = bounded::<GlobalInputEvent>(capacity);
     let receiver_for_drop = receiver.clone();
+    if let Ok(mut injector) = state.injector.lock() {
+        *injector = Some((sender.clone(), receiver.clone()));
+    }
 
     std::thread::Builder::new()
         .name("global-input-forwarder".to_string())
@@ -245,37 +926,77 @@ pub fn start_listener(
         .spawn(move || {
             // Note: macOS requires Accessibility permission for global input capture.
             let state_for_callback = Arc::clone(&listener_state);
+            let diagnostics_for_callback = Arc::clone(&diagnostics_state);
             let sender_for_callback = sender;
             let receiver_for_drop_callback = receiver_for_drop;
+            let app_for_callback = app.clone();
 
             let listen_result = rdev::listen(move |event| {
+                state_for_callback
+                    .listener_last_event_ms
+                    .store(now_timestamp_ms(), Ordering::Relaxed);
                 if !state_for_callback.forwarding.load(Ordering::Relaxed) {
                     return;
                 }
+                // Counted before the scope filter: the health check asks whether
+                // the OS delivers input at all, not whether we forward it.
+                let seen_before = state_for_callback
+                    .events_seen_since_start
+                    .fetch_add(1, Ordering::SeqCst);
+                // `start_listener` zeroes the counter, re-arming this.
+                if seen_before == 0 {
+                    emit_to_frontend(&app_for_callback, "first-input-detected", ());
+                }
+                if !state_for_callback.capture_scope().allows(&event.event_type) {
+                    return;
+                }
 
-                if let Some(payload) = normalize_event(&event) {
-                    state_for_callback
-                        .events_seen_since_start
-                        .fetch_add(1, Ordering::SeqCst);
-                    enqueue_with_drop_old(
-                        &sender_for_callback,
-                        &receiver_for_drop_callback,
-                        payload,
-                    );
+                match normalize_event(&event) {
+                    Ok(payload) => {
+                        if state_for_callback.suppressed.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        enqueue_with_drop_old(
+                            &sender_for_callback,
+                            &receiver_for_drop_callback,
+                            payload,
+                        );
+                    }
+                    Err(kind) => diagnostics_for_callback.record_dropped_input(kind),
                 }
             });
 
-            if let Err(err) = listen_result {
-                tracing::error!("global input listener exited with error: {err:?}");
-                diagnostics_state.record_error(
-                    "error".to_string(),
-                    format!("global input listener exited: {err:?}"),
-                    None,
-                );
-            }
+            let error = match listen_result {
+                Ok(()) => {
+                    tracing::warn!("global input listener returned");
+                    None
+                }
+                Err(err) => {
+                    tracing::error!("global input listener exited with error: {err:?}");
+                    diagnostics_state.record_error(
+                        "error".to_string(),
+                        format!("global input listener exited: {err:?}"),
+                        None,
+                    );
+                    Some(format!("{err:?}"))
+                }
+            };
 
+            if let Ok(mut injector) = listener_state.injector.lock() {
+                *injector = None;
+            }
             listener_state.forwarding.store(false, Ordering::SeqCst);
             listener_state.running.store(false, Ordering::SeqCst);
+
+            let payload = ListenerExitedPayload {
+                reason: if error.is_some() { "error" } else { "stopped" },
+                platform: platform_name(),
+                events_seen: listener_state
+                    .events_seen_since_start
+                    .load(Ordering::SeqCst),
+                error,
+            };
+            emit_to_frontend(&app, "listener-exited", payload);
         })
         .map_err(|err| {
             state.forwarding.store(false, Ordering::SeqCst);
@@ -297,58 +1018,516 @@ pub fn stop_listener(state: State<'_, SharedInputListenerState>) -> String {
     }
 }
 
-fn normalize_event(event: &Event) -> Option<GlobalInputEvent> {
-    let timestamp = event
-        .time
-        .duration_since(UNIX_EPOCH)
-        .map(|duration| duration.as_millis() as u64)
-        .unwrap_or(0);
+fn self_test_suggestion(
+    listener_started: bool,
+    events_seen: u64,
+    permission: PermissionState,
+) -> String {
+    if !listener_started {
+        return "The input listener could not start; check the diagnostics panel for errors."
+            .to_string();
+    }
+    if events_seen > 0 {
+        return "Input is reaching the pet. If it still doesn't react, check the motion map."
+            .to_string();
+    }
+    match (platform_name().as_str(), permission) {
+        ("macos", PermissionState::Granted) => {
+            "No events arrived. Try removing and re-adding the app under Input Monitoring."
+        }
+        ("macos", _) => {
+            "Grant Input Monitoring (and Accessibility) in System Settings, then restart the app."
+        }
+        ("linux", PermissionState::Denied) => {
+            "Global input isn't available on Wayland; run the app under X11 or XWayland."
+        }
+        _ => "No events arrived. Press a key or move the mouse during the test and try again.",
+    }
+    .to_string()
+}
 
-    match &event.event_type {
-        EventType::KeyPress(key) => Some(GlobalInputEvent {
-            r#type: "KeyPress".to_string(),
-            key_code: Some(key_to_string(key)),
-            button: None,
-            x: None,
-            y: None,
-            timestamp,
-        }),
-        EventType::KeyRelease(key) => Some(GlobalInputEvent {
-            r#type: "KeyRelease".to_string(),
+/// Starts the listener if needed, waits briefly for real input and reports what
+/// happened. Runs off the main thread because it blocks for the test window.
+#[tauri::command(async)]
+pub fn run_input_self_test(app: AppHandle) -> InputSelfTest {
+    let state = app.state::<SharedInputListenerState>();
+    let was_forwarding = state.forwarding.load(Ordering::SeqCst);
+
+    let mut listener_started = state.running.load(Ordering::SeqCst);
+    if !was_forwarding {
+        listener_started = match start_listener(
+            app.clone(),
+            app.state::<SharedInputListenerState>(),
+            app.state::<SharedDiagnosticsState>(),
+        ) {
+            Ok(_) => true,
+            Err(error) => {
+                tracing::warn!("input self-test could not start the listener: {error}");
+                false
+            }
+        };
+    }
+
+    let baseline = state.events_seen_since_start.load(Ordering::SeqCst);
+    let started_at = Instant::now();
+    let mut events_seen = 0;
+    while listener_started && started_at.elapsed() < SELF_TEST_WINDOW {
+        std::thread::sleep(SELF_TEST_POLL_INTERVAL);
+        events_seen = state
+            .events_seen_since_start
+            .load(Ordering::SeqCst)
+            .saturating_sub(baseline);
+        if events_seen > 0 || !state.running.load(Ordering::SeqCst) {
+            break;
+        }
+    }
+
+    if !was_forwarding {
+        stop_listener(app.state::<SharedInputListenerState>());
+    }
+
+    let permissions = refresh_permissions(&app);
+    let permission = if platform_name() == "linux" {
+        permissions.display_server
+    } else {
+        permissions.input_monitoring
+    };
+
+    InputSelfTest {
+        listener_started,
+        events_seen,
+        permission,
+        platform: platform_name(),
+        suggestion: self_test_suggestion(listener_started, events_seen, permission),
+    }
+}
+
+/// Pauses (or resumes) input events to the frontend, e.g. during a renderer
+/// reload. Diagnostics keep recording either way; on by default.
+#[tauri::command]
+pub fn set_frontend_emit(enabled: bool) -> bool {
+    FRONTEND_EMIT.store(enabled, Ordering::SeqCst);
+    enabled
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTraceEndedPayload {
+    reason: &'static str,
+}
+
+fn set_event_trace_internal(enabled: bool) -> Result<(), String> {
+    crate::set_trace_logging_for(module_path!(), enabled)?;
+    EVENT_TRACE.store(enabled, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Logs every forwarded input event to the file log at `trace` level, raising
+/// the log filter for this module while on. With `duration_ms` it switches
+/// itself off after that long and emits `event-trace-ended`.
+#[tauri::command]
+pub fn set_event_trace(
+    app: AppHandle,
+    enabled: bool,
+    duration_ms: Option<u64>,
+) -> Result<bool, String> {
+    let token = EVENT_TRACE_TOKEN.fetch_add(1, Ordering::SeqCst) + 1;
+    set_event_trace_internal(enabled)?;
+    tracing::info!("input event trace {}", if enabled { "on" } else { "off" });
+
+    if let Some(duration_ms) = duration_ms.filter(|_| enabled) {
+        std::thread::Builder::new()
+            .name("event-trace-timer".to_string())
+            .spawn(move || {
+                std::thread::sleep(Duration::from_millis(duration_ms));
+                if EVENT_TRACE_TOKEN.load(Ordering::SeqCst) != token {
+                    return;
+                }
+                if let Err(error) = set_event_trace_internal(false) {
+                    tracing::warn!("failed to end input event trace: {error}");
+                    return;
+                }
+                tracing::info!("input event trace off after {duration_ms} ms");
+                let payload = EventTraceEndedPayload { reason: "expired" };
+                if let Err(error) = app.emit("event-trace-ended", payload) {
+                    tracing::warn!("failed to emit event-trace-ended event: {error}");
+                }
+            })
+            .map_err(|error| format!("failed to start event trace timer: {error}"))?;
+    }
+    Ok(enabled)
+}
+
+#[tauri::command]
+pub fn get_input_channel_capacity(state: State<'_, SharedInputListenerState>) -> usize {
+    state.channel_capacity.load(Ordering::SeqCst)
+}
+
+/// Sets the input queue size and saves it. The channel is created by the
+/// `start_listener` call that spawns the listener thread; that thread keeps
+/// running after `stop_listener`, so the saved value is restored at launch and
+/// used by the next listener start after the app restarts.
+#[tauri::command]
+pub fn set_input_channel_capacity(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    n: usize,
+) -> Result<usize, String> {
+    let capacity = n.clamp(MIN_INPUT_CHANNEL_CAPACITY, MAX_INPUT_CHANNEL_CAPACITY);
+    state.channel_capacity.store(capacity, Ordering::SeqCst);
+    save_value(&app, CHANNEL_CAPACITY_KEY, &Some(capacity))?;
+    Ok(capacity)
+}
+
+/// Applies the saved input queue size, or the default when none is saved.
+pub(crate) fn restore_channel_capacity(app: &AppHandle) {
+    let saved: Option<usize> = load_value::<Option<usize>>(app, CHANNEL_CAPACITY_KEY).flatten();
+    app.state::<SharedInputListenerState>()
+        .channel_capacity
+        .store(
+            saved
+                .unwrap_or(DEFAULT_INPUT_CHANNEL_CAPACITY)
+                .clamp(MIN_INPUT_CHANNEL_CAPACITY, MAX_INPUT_CHANNEL_CAPACITY),
+            Ordering::SeqCst,
+        );
+}
+
+/// Sets the throttle interval for one event type. `0` removes throttling so
+/// events of that type are forwarded immediately.
+#[tauri::command]
+pub fn set_throttle_for(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    r#type: String,
+    ms: u64,
+) -> Result<HashMap<String, u64>, String> {
+    let before = state.config();
+    let intervals = {
+        let mut intervals = state
+            .throttle_intervals
+            .lock()
+            .map_err(|_| "throttle state unavailable".to_string())?;
+        if ms == 0 {
+            intervals.remove(&r#type);
+        } else {
+            intervals.insert(r#type, ms);
+        }
+        intervals.clone()
+    };
+    emit_tuning_changes(&app, &before, &state.config());
+    Ok(intervals)
+}
+
+#[tauri::command]
+pub fn set_adaptive_throttle(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    enabled: bool,
+) -> bool {
+    let before = state.config();
+    state.adaptive_throttle.store(enabled, Ordering::SeqCst);
+    emit_tuning_changes(&app, &before, &state.config());
+    enabled
+}
+
+#[tauri::command]
+pub fn get_held_inputs(state: State<'_, SharedInputListenerState>) -> HeldInputs {
+    state
+        .held_inputs
+        .lock()
+        .map(|tracker| tracker.snapshot())
+        .unwrap_or_default()
+}
+
+fn forwarder_poll_timings(state: &InputListenerState) -> ForwarderPollTimings {
+    ForwarderPollTimings {
+        active_ms: state.forwarder_poll_ms.load(Ordering::SeqCst),
+        idle_ms: state.forwarder_idle_poll_ms.load(Ordering::SeqCst),
+    }
+}
+
+#[tauri::command]
+pub fn get_forwarder_poll_ms(state: State<'_, SharedInputListenerState>) -> ForwarderPollTimings {
+    forwarder_poll_timings(&state)
+}
+
+/// Overrides how long the forwarder waits for events before flushing throttled
+/// ones. Lower values cut latency at the cost of CPU; both are clamped.
+#[tauri::command]
+pub fn set_forwarder_poll_ms(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    active: u64,
+    idle: u64,
+) -> ForwarderPollTimings {
+    let before = state.config();
+    state.forwarder_poll_ms.store(
+        active.clamp(MIN_FORWARDER_POLL_MS, MAX_FORWARDER_POLL_MS),
+        Ordering::SeqCst,
+    );
+    state.forwarder_idle_poll_ms.store(
+        idle.clamp(MIN_FORWARDER_IDLE_POLL_MS, MAX_FORWARDER_POLL_MS),
+        Ordering::SeqCst,
+    );
+    emit_tuning_changes(&app, &before, &state.config());
+    forwarder_poll_timings(&state)
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputBatching {
+    /// 0 when batching is off.
+    pub interval_ms: u64,
+    pub per_event: bool,
+}
+
+fn input_batching(state: &InputListenerState) -> InputBatching {
+    InputBatching {
+        interval_ms: state.batch_interval_ms.load(Ordering::SeqCst),
+        per_event: state.batch_keeps_per_event.load(Ordering::SeqCst),
+    }
+}
+
+#[tauri::command]
+pub fn get_input_batching(state: State<'_, SharedInputListenerState>) -> InputBatching {
+    input_batching(&state)
+}
+
+/// Batches button, key and wheel events into one `global-input-batch` event
+/// per `interval_ms` (0 turns batching off). Fewer IPC calls during bursts, at
+/// the cost of up to `interval_ms` extra latency; throttled types such as
+/// `MouseMove` are unaffected. With `per_event`, `global-input` keeps firing
+/// for each event and no batches are emitted, for consumers that need the
+/// lowest latency. The interval is capped at 250 ms.
+#[tauri::command]
+pub fn set_input_batching(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    interval_ms: u64,
+    per_event: Option<bool>,
+) -> InputBatching {
+    state
+        .batch_interval_ms
+        .store(interval_ms.min(MAX_INPUT_BATCH_MS), Ordering::SeqCst);
+    if let Some(per_event) = per_event {
+        state
+            .batch_keeps_per_event
+            .store(per_event, Ordering::SeqCst);
+    }
+    let batching = input_batching(&state);
+    if let Err(error) = app.emit("input-batching-changed", batching) {
+        tracing::warn!("failed to emit input-batching-changed event: {error}");
+    }
+    batching
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadStatus {
+    pub name: &'static str,
+    pub running: bool,
+    /// Unix ms of the thread's last sign of life, if any.
+    pub last_activity_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadHealth {
+    pub listener: ThreadStatus,
+    pub forwarder: ThreadStatus,
+    pub health_check: ThreadStatus,
+    /// Unix ms of the forwarder's last loop iteration.
+    pub forwarder_last_tick_ms: Option<u64>,
+    /// The forwarder is running but has stopped looping.
+    pub forwarder_stalled: bool,
+    pub checked_at_ms: u64,
+}
+
+fn nonzero(timestamp_ms: u64) -> Option<u64> {
+    (timestamp_ms != 0).then_some(timestamp_ms)
+}
+
+fn thread_health(state: &InputListenerState) -> ThreadHealth {
+    let now = now_timestamp_ms();
+    let forwarder_running = state.forwarder_alive.load(Ordering::SeqCst);
+    let forwarder_last_tick_ms = nonzero(state.forwarder_last_tick_ms.load(Ordering::SeqCst));
+    let stall_after_ms = state
+        .forwarder_idle_poll_ms
+        .load(Ordering::SeqCst)
+        .max(state.forwarder_poll_ms.load(Ordering::SeqCst))
+        + FORWARDER_STALL_GRACE_MS;
+    let forwarder_stalled = forwarder_running
+        && forwarder_last_tick_ms.is_some_and(|tick| now.saturating_sub(tick) > stall_after_ms);
+
+    ThreadHealth {
+        // rdev offers no way to observe its hook, so the listener is as alive
+        // as the flag `start_listener` set and the last event it delivered.
+        listener: ThreadStatus {
+            name: "global-input-listener",
+            running: state.running.load(Ordering::SeqCst),
+            last_activity_ms: nonzero(state.listener_last_event_ms.load(Ordering::SeqCst)),
+        },
+        forwarder: ThreadStatus {
+            name: "global-input-forwarder",
+            running: forwarder_running,
+            last_activity_ms: forwarder_last_tick_ms,
+        },
+        health_check: ThreadStatus {
+            name: "global-input-health-check",
+            running: state.health_checks_pending.load(Ordering::SeqCst) > 0,
+            last_activity_ms: nonzero(state.health_check_started_ms.load(Ordering::SeqCst)),
+        },
+        forwarder_last_tick_ms,
+        forwarder_stalled,
+        checked_at_ms: now,
+    }
+}
+
+/// Reports which input threads are believed alive, and whether the forwarder
+/// has stopped looping while still marked running.
+#[tauri::command]
+pub fn get_thread_health(state: State<'_, SharedInputListenerState>) -> ThreadHealth {
+    thread_health(&state)
+}
+
+/// Applies the saved capture scope, or `full` when none is saved.
+pub(crate) fn restore_capture_scope(app: &AppHandle) {
+    let saved: Option<String> = load_value(app, CAPTURE_SCOPE_KEY);
+    let scope = saved
+        .and_then(|scope| CaptureScope::parse(&scope).ok())
+        .unwrap_or(CaptureScope::Full);
+    app.state::<SharedInputListenerState>()
+        .capture_scope
+        .store(scope as u8, Ordering::SeqCst);
+}
+
+/// Applies the saved idle threshold, or the default when none is saved.
+pub(crate) fn restore_idle_threshold(app: &AppHandle) {
+    let saved: Option<u64> = load_value::<Option<u64>>(app, IDLE_THRESHOLD_KEY).flatten();
+    app.state::<SharedInputListenerState>()
+        .idle_threshold_ms
+        .store(
+            saved
+                .unwrap_or(DEFAULT_IDLE_THRESHOLD_MS)
+                .max(MIN_IDLE_THRESHOLD_MS),
+            Ordering::SeqCst,
+        );
+}
+
+#[tauri::command]
+pub fn get_idle_threshold_ms(state: State<'_, SharedInputListenerState>) -> u64 {
+    state.idle_threshold_ms.load(Ordering::SeqCst)
+}
+
+/// Sets how long without input before `input-idle-changed` and `system-idle`
+/// report idle; clamped to at least 10 s. Both watchers pick it up on their next
+/// tick.
+#[tauri::command]
+pub fn set_idle_threshold_ms(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    ms: u64,
+) -> Result<u64, String> {
+    let ms = ms.max(MIN_IDLE_THRESHOLD_MS);
+    state.idle_threshold_ms.store(ms, Ordering::SeqCst);
+    save_value(&app, IDLE_THRESHOLD_KEY, &Some(ms))?;
+    Ok(ms)
+}
+
+#[tauri::command]
+pub fn get_capture_scope(state: State<'_, SharedInputListenerState>) -> String {
+    state.capture_scope().as_str().to_string()
+}
+
+/// Limits capture to `"full"`, `"mouse_only"` or `"keyboard_only"`. Applies to
+/// the running listener immediately.
+#[tauri::command]
+pub fn set_capture_scope(
+    app: AppHandle,
+    state: State<'_, SharedInputListenerState>,
+    scope: String,
+) -> Result<String, String> {
+    let scope = CaptureScope::parse(&scope)?;
+    state.capture_scope.store(scope as u8, Ordering::SeqCst);
+    save_value(&app, CAPTURE_SCOPE_KEY, &scope.as_str())?;
+    Ok(scope.as_str().to_string())
+}
+
+/// The forwarded fields for one supported rdev event; one constructor per shape.
+#[derive(Debug, PartialEq)]
+struct EventFields {
+    r#type: &'static str,
+    key_code: Option<String>,
+    button: Option<String>,
+    x: Option<f64>,
+    y: Option<f64>,
+}
+
+impl EventFields {
+    fn key(r#type: &'static str, key: &Key) -> Self {
+        Self {
+            r#type,
             key_code: Some(key_to_string(key)),
             button: None,
             x: None,
             y: None,
-            timestamp,
-        }),
-        EventType::MouseMove { x, y } => Some(GlobalInputEvent {
-            r#type: "MouseMove".to_string(),
-            key_code: None,
-            button: None,
-            x: Some(*x),
-            y: Some(*y),
-            timestamp,
-        }),
-        EventType::ButtonPress(button) => Some(GlobalInputEvent {
-            r#type: "ButtonPress".to_string(),
+        }
+    }
+
+    fn button(r#type: &'static str, button: &Button) -> Self {
+        Self {
+            r#type,
             key_code: None,
             button: Some(button_to_string(button)),
             x: None,
             y: None,
-            timestamp,
-        }),
-        EventType::ButtonRelease(button) => Some(GlobalInputEvent {
-            r#type: "ButtonRelease".to_string(),
+        }
+    }
+
+    fn pointer(r#type: &'static str, x: f64, y: f64) -> Self {
+        Self {
+            r#type,
             key_code: None,
-            button: Some(button_to_string(button)),
-            x: None,
-            y: None,
-            timestamp,
-        }),
-        _ => None,
+            button: None,
+            x: Some(x),
+            y: Some(y),
+        }
+    }
+}
+
+/// Maps an rdev event type to forwarded fields, or to the label it is counted
+/// under in diagnostics when it isn't forwarded. Everything dropped counts as
+/// `"Unknown"`.
+fn event_fields(event_type: &EventType) -> Result<EventFields, &'static str> {
+    match event_type {
+        EventType::KeyPress(key) => Ok(EventFields::key("KeyPress", key)),
+        EventType::KeyRelease(key) => Ok(EventFields::key("KeyRelease", key)),
+        EventType::ButtonPress(button) => Ok(EventFields::button("ButtonPress", button)),
+        EventType::ButtonRelease(button) => Ok(EventFields::button("ButtonRelease", button)),
+        EventType::MouseMove { x, y } => Ok(EventFields::pointer("MouseMove", *x, *y)),
+        EventType::Wheel { .. } => Err("Unknown"),
     }
 }
 
+fn normalize_event(event: &Event) -> Result<GlobalInputEvent, &'static str> {
+    let fields = event_fields(&event.event_type)?;
+    let timestamp = event
+        .time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0);
+
+    Ok(GlobalInputEvent {
+        r#type: fields.r#type.to_string(),
+        key_code: fields.key_code,
+        button: fields.button,
+        x: fields.x,
+        y: fields.y,
+        distance: None,
+        timestamp,
+    })
+}
+
 fn key_to_string(key: &Key) -> String {
     format!("{key:?}")
 }
@@ -356,3 +1535,128 @@ fn key_to_string(key: &Key) -> String {
 fn button_to_string(button: &Button) -> String {
     format!("{button:?}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    fn event(event_type: EventType) -> Event {
+        Event {
+            time: UNIX_EPOCH + Duration::from_millis(1_234),
+            name: None,
+            event_type,
+        }
+    }
+
+    #[test]
+    fn supported_event_types_are_normalized() {
+        let cases = [
+            (
+                EventType::KeyPress(Key::KeyA),
+                "KeyPress",
+                Some("KeyA"),
+                None,
+            ),
+            (
+                EventType::KeyRelease(Key::KeyA),
+                "KeyRelease",
+                Some("KeyA"),
+                None,
+            ),
+            (
+                EventType::ButtonPress(Button::Left),
+                "ButtonPress",
+                None,
+                Some("Left"),
+            ),
+            (
+                EventType::ButtonRelease(Button::Right),
+                "ButtonRelease",
+                None,
+                Some("Right"),
+            ),
+        ];
+
+        for (event_type, expected_type, key_code, button) in cases {
+            let payload = normalize_event(&event(event_type)).unwrap();
+            assert_eq!(payload.r#type, expected_type);
+            assert_eq!(payload.key_code.as_deref(), key_code);
+            assert_eq!(payload.button.as_deref(), button);
+            assert_eq!(payload.x, None);
+            assert_eq!(payload.timestamp, 1_234);
+        }
+    }
+
+    #[test]
+    fn mouse_move_keeps_coordinates() {
+        let payload = normalize_event(&event(EventType::MouseMove { x: 10.5, y: -3.0 })).unwrap();
+        assert_eq!(payload.r#type, "MouseMove");
+        assert_eq!((payload.x, payload.y), (Some(10.5), Some(-3.0)));
+        assert_eq!(payload.key_code, None);
+    }
+
+    #[test]
+    fn unsupported_event_types_are_counted_as_unknown() {
+        let wheel = event(EventType::Wheel {
+            delta_x: 0,
+            delta_y: -1,
+        });
+        assert_eq!(normalize_event(&wheel).unwrap_err(), "Unknown");
+    }
+
+    #[test]
+    fn exact_duplicate_key_events_are_forwarded_once() {
+        let press = normalize_event(&event(EventType::KeyPress(Key::KeyA))).unwrap();
+        let release = normalize_event(&event(EventType::KeyRelease(Key::KeyA))).unwrap();
+        let mut later_press = press.clone();
+        later_press.timestamp += 1;
+
+        let stream = [
+            press.clone(),
+            press.clone(),
+            release.clone(),
+            release,
+            later_press,
+            press,
+        ];
+        let mut deduplicator = KeyDeduplicator::default();
+        let forwarded: Vec<_> = stream
+            .iter()
+            .filter(|payload| !deduplicator.is_duplicate(payload))
+            .map(|payload| (payload.r#type.as_str(), payload.timestamp))
+            .collect();
+
+        assert_eq!(
+            forwarded,
+            [
+                ("KeyPress", 1_234),
+                ("KeyRelease", 1_234),
+                ("KeyPress", 1_235),
+                ("KeyPress", 1_234),
+            ]
+        );
+    }
+
+    #[test]
+    fn frame_batch_is_released_once_its_interval_passed() {
+        let press = normalize_event(&event(EventType::KeyPress(Key::KeyA))).unwrap();
+        let mut batcher = FrameBatcher::default();
+        assert!(batcher.take_if_due(Duration::ZERO, false).is_none());
+
+        batcher.push(press.clone());
+        batcher.push(press);
+        assert!(batcher
+            .take_if_due(Duration::from_secs(60), false)
+            .is_none());
+        assert_eq!(batcher.take_if_due(Duration::ZERO, false).unwrap().len(), 2);
+        assert!(batcher.take_if_due(Duration::ZERO, true).is_none());
+    }
+
+    #[test]
+    fn events_before_epoch_fall_back_to_zero_timestamp() {
+        let mut press = event(EventType::KeyPress(Key::Space));
+        press.time = SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(normalize_event(&press).unwrap().timestamp, 0);
+    }
+}
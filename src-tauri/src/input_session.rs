@@ -0,0 +1,181 @@
+use crate::diagnostics::{now_timestamp_ms, GlobalInputEvent, SharedDiagnosticsState};
+use crate::input_listener::SharedInputListenerState;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const SESSIONS_DIR: &str = "input-sessions";
+const MAX_REPLAY_SPEED: f64 = 100.0;
+
+static REPLAYING: AtomicBool = AtomicBool::new(false);
+
+/// One line of a session file: the normalized event and when it arrived,
+/// relative to the start of the recording.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionEntry {
+    offset_ms: u64,
+    event: GlobalInputEvent,
+}
+
+pub(crate) struct SessionRecorder {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    started: Instant,
+}
+
+impl SessionRecorder {
+    fn create(path: PathBuf) -> Result<Self, String> {
+        let file = File::create(&path)
+            .map_err(|error| format!("failed to create {}: {error}", path.display()))?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            path,
+            started: Instant::now(),
+        })
+    }
+
+    pub(crate) fn record(&mut self, event: &GlobalInputEvent) {
+        let entry = SessionEntry {
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+        let written = serde_json::to_writer(&mut self.writer, &entry)
+            .map_err(|error| error.to_string())
+            .and_then(|_| {
+                self.writer
+                    .write_all(b"\n")
+                    .map_err(|error| error.to_string())
+            });
+        if let Err(error) = written {
+            tracing::warn!("failed to record input session event: {error}");
+        }
+    }
+
+    fn finish(mut self) -> Result<PathBuf, String> {
+        self.writer
+            .flush()
+            .map_err(|error| format!("failed to write {}: {error}", self.path.display()))?;
+        Ok(self.path)
+    }
+}
+
+/// Replay injects synthetic input into every listener, so it is only
+/// available in debug builds or with `ALLOW_INPUT_SIMULATION=1`.
+fn input_simulation_allowed() -> Result<(), String> {
+    static ALLOWED: Lazy<bool> = Lazy::new(|| {
+        cfg!(debug_assertions)
+            || std::env::var("ALLOW_INPUT_SIMULATION")
+                .map(|value| matches!(value.as_str(), "1" | "true"))
+                .unwrap_or(false)
+    });
+
+    if *ALLOWED {
+        Ok(())
+    } else {
+        Err("input simulation is disabled; set ALLOW_INPUT_SIMULATION=1 to enable it".to_string())
+    }
+}
+
+fn read_session(path: &Path) -> Result<Vec<SessionEntry>, String> {
+    let file =
+        File::open(path).map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            let line =
+                line.map_err(|error| format!("failed to read {}: {error}", path.display()))?;
+            serde_json::from_str(&line)
+                .map_err(|error| format!("invalid session entry on line {}: {error}", index + 1))
+        })
+        .collect()
+}
+
+/// Starts writing every normalized event the listener accepts to a new session
+/// file under the log directory, redacted like the diagnostics buffer. Returns
+/// the file path.
+#[tauri::command]
+pub fn start_recording_session(
+    app: AppHandle,
+    state: tauri::State<'_, SharedInputListenerState>,
+    diagnostics: tauri::State<'_, SharedDiagnosticsState>,
+) -> Result<String, String> {
+    if !diagnostics.input_recording() {
+        return Err("input recording is turned off".to_string());
+    }
+    let dir = crate::storage_root::log_dir(&app)?.join(SESSIONS_DIR);
+    fs::create_dir_all(&dir)
+        .map_err(|error| format!("failed to create {}: {error}", dir.display()))?;
+
+    let path = dir.join(format!("session-{}.jsonl", now_timestamp_ms()));
+    let recorder = SessionRecorder::create(path.clone())?;
+    if let Some(previous) = state.replace_session_recorder(Some(recorder)) {
+        if let Err(error) = previous.finish() {
+            tracing::warn!("previous input session was not saved cleanly: {error}");
+        }
+    }
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub fn stop_recording_session(
+    state: tauri::State<'_, SharedInputListenerState>,
+) -> Result<String, String> {
+    let recorder = state
+        .replace_session_recorder(None)
+        .ok_or_else(|| "no input session is being recorded".to_string())?;
+    let path = recorder.finish()?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Feeds a recorded session back through the running listener's forwarder,
+/// keeping the recorded gaps divided by `speed`. Events are re-stamped with the
+/// current time. Returns the number of events scheduled.
+#[tauri::command]
+pub fn replay_session(app: AppHandle, path: String, speed: f64) -> Result<usize, String> {
+    input_simulation_allowed()?;
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err("speed must be a positive number".to_string());
+    }
+    let speed = speed.min(MAX_REPLAY_SPEED);
+
+    let entries = read_session(Path::new(&path))?;
+    let count = entries.len();
+    if REPLAYING.swap(true, Ordering::SeqCst) {
+        return Err("an input session is already being replayed".to_string());
+    }
+
+    let state = std::sync::Arc::clone(app.state::<SharedInputListenerState>().inner());
+    let spawned = std::thread::Builder::new()
+        .name("input-session-replay".to_string())
+        .spawn(move || {
+            let started = Instant::now();
+            for entry in entries {
+                let due = Duration::from_secs_f64(entry.offset_ms as f64 / 1000.0 / speed);
+                if let Some(wait) = due.checked_sub(started.elapsed()) {
+                    std::thread::sleep(wait);
+                }
+
+                let mut event = entry.event;
+                event.timestamp = now_timestamp_ms();
+                if let Err(error) = state.inject_event(event) {
+                    tracing::warn!("input session replay stopped: {error}");
+                    break;
+                }
+            }
+            REPLAYING.store(false, Ordering::SeqCst);
+        });
+
+    if let Err(error) = spawned {
+        REPLAYING.store(false, Ordering::SeqCst);
+        return Err(format!("failed to start replay thread: {error}"));
+    }
+    Ok(count)
+}
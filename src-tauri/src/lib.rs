@@ -1,47 +1,190 @@
+mod active_window;
+mod config_backup;
 mod diagnostics;
+mod drag;
+mod effective_config;
+mod flash;
 mod input_listener;
+mod input_session;
+mod library;
+mod log_tail;
+mod model_archive;
+mod model_fingerprint;
 mod model_scan;
+mod model_validate;
+mod model_warm;
+mod motions;
+mod permissions;
+mod persist;
+mod plugin_status;
+mod presets;
+mod profiles;
+mod reaction_cooldown;
+mod session_info;
+mod shortcuts;
+mod storage_root;
+mod system_idle;
+mod tray_icon;
+mod updates;
+mod window_placement;
+mod window_reactions;
+mod window_state;
 
-use std::process::Command;
+use std::collections::HashMap;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
-    Arc,
+    Arc, Mutex,
 };
 
-use diagnostics::{DiagnosticsSnapshot, DiagnosticsState, SharedDiagnosticsState};
-use input_listener::{start_listener, stop_listener, InputListenerState};
-use model_scan::find_model3_json;
-use once_cell::sync::OnceCell;
+use config_backup::{export_config, import_config};
+use diagnostics::{
+    DiagnosticErrorRecord, DiagnosticsSnapshot, DiagnosticsState, InputAnonymization, MetricBounds,
+    SharedDiagnosticsState, WindowEventRecord,
+};
+use drag::start_window_drag;
+use effective_config::get_effective_config;
+use flash::flash_over_fullscreen;
+use input_listener::{
+    get_capture_scope, get_forwarder_poll_ms, get_held_inputs, get_idle_threshold_ms,
+    get_input_batching, get_input_channel_capacity, get_thread_health, run_input_self_test,
+    set_adaptive_throttle, set_capture_scope, set_event_trace, set_forwarder_poll_ms,
+    set_frontend_emit, set_idle_threshold_ms, set_input_batching, set_input_channel_capacity,
+    set_throttle_for, start_listener, stop_listener, InputListenerState, SharedInputListenerState,
+};
+use input_session::{replay_session, start_recording_session, stop_recording_session};
+use library::{
+    add_library_root, clear_recent_models, get_library_roots, is_favorite, list_favorites,
+    list_recent_models, push_recent_model, relink_model, remove_library_root, set_active_model,
+    set_recent_models_limit, toggle_favorite,
+};
+use model_archive::extract_model;
+use model_fingerprint::{find_duplicate_models, locate_moved_model, model_fingerprint};
+use model_scan::{
+    cancel_all_scans, find_all_model3_json, find_model3_json, list_active_scans, search_models,
+};
+use model_validate::{
+    benchmark_model_parse, list_animations_in_dir, list_model_animations, model_canvas_size,
+    model_disk_size, pick_and_validate_model, set_model3_max_bytes, set_texture_warning_threshold,
+};
+use model_warm::warm_model;
+use motions::{random_motion, trigger_motion};
+use once_cell::sync::{Lazy, OnceCell};
+use permissions::{ensure_input_permission, get_permissions_status};
+use plugin_status::get_plugin_status;
+use presets::{apply_preset, get_fps_cap, list_presets};
+use profiles::{
+    delete_profile, export_profile, import_profile, list_profiles, load_profile, save_profile,
+};
+use reaction_cooldown::{get_reaction_cooldown_ms, set_reaction_cooldown_ms};
 use serde::Serialize;
+use session_info::get_session_info;
+use shortcuts::{get_shortcuts, set_shortcut, ShortcutBindings};
+use storage_root::{get_store_path, set_storage_root};
+use system_idle::get_system_idle_ms;
 use tauri::{
-    menu::{Menu, MenuItem},
-    tray::TrayIconBuilder,
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
+    webview::PageLoadEvent,
     AppHandle, Emitter, Manager, State, WindowEvent,
 };
-use tracing_subscriber::EnvFilter;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_opener::OpenerExt;
+use tracing_subscriber::{filter::LevelFilter, layer::SubscriberExt, reload, EnvFilter, Layer};
+use tray_icon::{reset_tray_icon, set_tray_icon};
+use updates::{
+    check_for_update, get_last_update_check, install_pending_update, set_update_check_interval,
+    verify_pending_update,
+};
+use window_placement::{
+    center_pet, get_monitors, get_snap_config, get_window_geometry, nudge_pet,
+    set_focus_debounce_ms, set_relative_anchor, set_snap_config, snap_to,
+};
+use window_reactions::{get_window_reaction_rules, set_window_reaction_rules};
+use window_state::{apply_window_config, capture_window_state, restore_window_state};
 
 const MENU_SHOW_HIDE: &str = "tray_show_hide";
 const MENU_OPEN_SETTINGS: &str = "tray_open_settings";
 const MENU_TOGGLE_CLICK_THROUGH: &str = "tray_toggle_click_through";
 const MENU_TOGGLE_LOCK: &str = "tray_toggle_lock";
 const MENU_TOGGLE_SNAP: &str = "tray_toggle_snap";
+const MENU_TOGGLE_GHOST: &str = "tray_toggle_ghost";
+const MENU_TOGGLE_DND: &str = "tray_toggle_dnd";
 const MENU_QUIT: &str = "tray_quit";
+const MENU_MODEL_PREFIX: &str = "tray_model:";
+const TRAY_ID: &str = "pet-tray";
+
+// Section ids rendered by SettingsPanel.tsx.
+const SETTINGS_SECTIONS: [&str; 7] = [
+    "model",
+    "scale",
+    "position",
+    "motion-map",
+    "autostart",
+    "updates",
+    "diagnostics",
+];
+
+const DO_NOT_DISTURB_KEY: &str = "doNotDisturb";
+const INPUT_RECORDING_KEY: &str = "inputRecording";
+const INPUT_ANONYMIZATION_KEY: &str = "inputAnonymization";
+const TRAY_DOUBLE_CLICK_KEY: &str = "trayDoubleClickAction";
+const TRAY_DOUBLE_CLICK_ACTIONS: [&str; 3] = ["toggle_visibility", "open_settings", "toggle_lock"];
+// Store keys mirrored by the frontend in src/lib/settings.ts.
+const WINDOW_LOCKED_KEY: &str = "windowLocked";
+const SNAP_ENABLED_KEY: &str = "snapEnabled";
+const PET_SETTINGS_KEYS: [&str; 4] = ["modelPath", "scale", "pos", "motionMap"];
+const GHOST_OPACITY: f64 = 0.35;
+
+const LOG_FILE_PREFIX: &str = "live2d-desktop-pet.log";
+const ERROR_LOG_PREFIX: &str = "errors.log";
+const MAX_TAIL_LOG_LINES: usize = 2_000;
+// `NonBlocking` has no synchronous flush; its worker drains continuously, so a
+// short pause is enough for lines logged just before the read to land.
+const LOG_DRAIN_WAIT: std::time::Duration = std::time::Duration::from_millis(50);
 
 static LOG_GUARD: OnceCell<tracing_appender::non_blocking::WorkerGuard> = OnceCell::new();
+static ERROR_LOG_GUARD: OnceCell<tracing_appender::non_blocking::WorkerGuard> = OnceCell::new();
+static LOG_DIR: OnceCell<std::path::PathBuf> = OnceCell::new();
+/// The file log's filter and the directives it started with, for temporary
+/// verbose tracing.
+static LOG_FILTER: OnceCell<(LogFilterHandle, String)> = OnceCell::new();
+
+type LogFilterHandle = reload::Handle<EnvFilter, tracing_subscriber::Registry>;
+/// Held while looking up or recreating the settings window, so two callers can't
+/// both find it missing and build a second one.
+static SETTINGS_WINDOW_LOCK: Mutex<()> = Mutex::new(());
+/// Section to navigate to once a recreated settings window has loaded.
+static PENDING_SETTINGS_SECTION: Mutex<Option<String>> = Mutex::new(None);
 
 struct UiState {
     click_through: AtomicBool,
     locked: AtomicBool,
     snap_enabled: AtomicBool,
+    do_not_disturb: AtomicBool,
+    always_on_top: AtomicBool,
+    ghost_mode: AtomicBool,
+    /// Click-through/lock values from before ghost mode, restored when it ends.
+    ghost_restore: Mutex<Option<GhostRestore>>,
     quitting: AtomicBool,
 }
 
+#[derive(Clone, Copy)]
+struct GhostRestore {
+    click_through: bool,
+    locked: bool,
+}
+
 impl Default for UiState {
     fn default() -> Self {
         Self {
             click_through: AtomicBool::new(false),
             locked: AtomicBool::new(true),
             snap_enabled: AtomicBool::new(true),
+            do_not_disturb: AtomicBool::new(false),
+            // Matches `alwaysOnTop` for the main window in tauri.conf.json.
+            always_on_top: AtomicBool::new(true),
+            ghost_mode: AtomicBool::new(false),
+            ghost_restore: Mutex::new(None),
             quitting: AtomicBool::new(false),
         }
     }
@@ -65,52 +208,239 @@ struct SnapPayload {
     enabled: bool,
 }
 
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DoNotDisturbPayload {
+    enabled: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsChangedPayload {
+    keys: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogPaths {
+    log_dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    current_log: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_log: Option<String>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LogDirStats {
+    file_count: usize,
+    total_bytes: u64,
+    oldest_ms: Option<u64>,
+    newest_ms: Option<u64>,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsNavigatePayload {
+    section: String,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GhostModePayload {
+    enabled: bool,
+    /// Opacity the renderer should apply to the pet.
+    opacity: f64,
+}
+
 fn init_logging(app: &tauri::App) -> Result<(), String> {
     if LOG_GUARD.get().is_some() {
         return Ok(());
     }
 
-    let log_dir = app
-        .path()
-        .app_log_dir()
-        .map_err(|error| format!("failed to resolve app log dir: {error}"))?;
+    let log_dir = storage_root::log_dir(app.handle())?;
 
     std::fs::create_dir_all(&log_dir)
         .map_err(|error| format!("failed to create log dir {}: {error}", log_dir.display()))?;
 
-    let file_appender = tracing_appender::rolling::daily(log_dir, "live2d-desktop-pet.log");
+    let file_appender = tracing_appender::rolling::daily(&log_dir, LOG_FILE_PREFIX);
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
 
-    let env_filter =
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let directives = std::env::var(EnvFilter::DEFAULT_ENV)
+        .ok()
+        .filter(|directives| EnvFilter::try_new(directives).is_ok())
+        .unwrap_or_else(|| "info".to_string());
+    let (env_filter, filter_handle) = reload::Layer::new(EnvFilter::new(&directives));
 
-    let subscriber = tracing_subscriber::fmt()
+    let full_layer = tracing_subscriber::fmt::layer()
         .with_ansi(false)
         .with_target(true)
-        .with_env_filter(env_filter)
         .with_writer(non_blocking)
-        .finish();
+        .with_filter(env_filter);
+
+    // WARN/ERROR only, so problems can be found without wading through debug output.
+    let (error_layer, error_guard) = if error_log_enabled() {
+        let error_appender = tracing_appender::rolling::daily(&log_dir, ERROR_LOG_PREFIX);
+        let (error_writer, error_guard) = tracing_appender::non_blocking(error_appender);
+        let layer = tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_target(true)
+            .with_writer(error_writer)
+            .with_filter(LevelFilter::WARN);
+        (Some(layer), Some(error_guard))
+    } else {
+        (None, None)
+    };
+
+    let subscriber = tracing_subscriber::registry()
+        .with(full_layer)
+        .with(error_layer);
 
     tracing::subscriber::set_global_default(subscriber)
         .map_err(|error| format!("failed to initialize tracing subscriber: {error}"))?;
 
     let _ = LOG_GUARD.set(guard);
+    if let Some(error_guard) = error_guard {
+        let _ = ERROR_LOG_GUARD.set(error_guard);
+    }
+    let _ = LOG_DIR.set(log_dir);
+    let _ = LOG_FILTER.set((filter_handle, directives));
+    storage_root::report_fallback();
     Ok(())
 }
 
+/// Raises the file log to `trace` for `target` on top of the startup filter,
+/// or puts the startup filter back.
+fn set_trace_logging_for(target: &str, enabled: bool) -> Result<(), String> {
+    let (handle, directives) = LOG_FILTER
+        .get()
+        .ok_or_else(|| "file logging is not initialized".to_string())?;
+    let filter = if enabled {
+        EnvFilter::new(format!("{directives},{target}=trace"))
+    } else {
+        EnvFilter::new(directives)
+    };
+    handle
+        .reload(filter)
+        .map_err(|error| format!("failed to reload log filter: {error}"))
+}
+
+/// The separate WARN/ERROR log is on unless `ERROR_LOG` is `0` or `false`.
+fn error_log_enabled() -> bool {
+    std::env::var("ERROR_LOG")
+        .map(|value| !matches!(value.as_str(), "0" | "false"))
+        .unwrap_or(true)
+}
+
+/// The daily main and error log files with their size and modification time.
+fn rolling_log_files(
+    log_dir: &std::path::Path,
+) -> Vec<(std::path::PathBuf, u64, std::time::SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(log_dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with(LOG_FILE_PREFIX) || name.starts_with(ERROR_LOG_PREFIX)
+        })
+        .filter_map(|entry| {
+            let metadata = entry
+                .metadata()
+                .ok()
+                .filter(|metadata| metadata.is_file())?;
+            Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+        })
+        .collect()
+}
+
+/// The daily appender suffixes the date, so the active file is the newest
+/// match for `prefix`.
+fn active_log_file(log_dir: &std::path::Path, prefix: &str) -> Option<std::path::PathBuf> {
+    rolling_log_files(log_dir)
+        .into_iter()
+        .filter(|(path, _, _)| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(prefix))
+        })
+        .max_by_key(|(_, _, modified)| *modified)
+        .map(|(path, _, _)| path)
+}
+
+fn current_log_file(log_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+    active_log_file(log_dir, LOG_FILE_PREFIX)
+}
+
+/// Keeps the window lifecycle in diagnostics for `get_window_event_log`.
+fn record_window_event(window: &tauri::Window, event: &WindowEvent) {
+    let (kind, detail) = match event {
+        WindowEvent::Moved(position) => ("Moved", Some(format!("{},{}", position.x, position.y))),
+        WindowEvent::Resized(size) => ("Resized", Some(format!("{}x{}", size.width, size.height))),
+        WindowEvent::Focused(focused) => ("Focused", Some(focused.to_string())),
+        WindowEvent::CloseRequested { .. } => ("CloseRequested", None),
+        WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+            ("ScaleFactorChanged", Some(scale_factor.to_string()))
+        }
+        _ => return,
+    };
+    window
+        .app_handle()
+        .state::<SharedDiagnosticsState>()
+        .record_window_event(window.label(), kind, detail);
+}
+
 fn record_backend_error(app: &AppHandle, message: String) {
     let diagnostics = app.state::<SharedDiagnosticsState>();
     diagnostics.record_error("error".to_string(), message, None);
 }
 
-fn main_window(app: &AppHandle) -> Result<tauri::WebviewWindow, String> {
-    app.get_webview_window("main")
-        .ok_or_else(|| "main window not found".to_string())
+/// Looks up a window by label. A miss is also recorded as a diagnostic with
+/// `context` (the command or event that needed it), since callers often drop errors.
+fn require_window(
+    app: &AppHandle,
+    label: &str,
+    context: &str,
+) -> Result<tauri::WebviewWindow, String> {
+    app.get_webview_window(label).ok_or_else(|| {
+        let message = format!("{label} window not found");
+        tracing::error!("{message} (needed by {context})");
+        app.state::<SharedDiagnosticsState>().record_error(
+            "error".to_string(),
+            message.clone(),
+            Some(context.to_string()),
+        );
+        message
+    })
 }
 
-fn settings_window(app: &AppHandle) -> Result<tauri::WebviewWindow, String> {
-    app.get_webview_window("settings")
-        .ok_or_else(|| "settings window not found".to_string())
+fn main_window(app: &AppHandle, context: &str) -> Result<tauri::WebviewWindow, String> {
+    require_window(app, "main", context)
+}
+
+/// The settings window, rebuilt from its tauri.conf.json entry if it was
+/// destroyed. The bool is true when it was just created.
+fn settings_window_or_create(app: &AppHandle) -> Result<(tauri::WebviewWindow, bool), String> {
+    let _guard = SETTINGS_WINDOW_LOCK
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(window) = app.get_webview_window("settings") {
+        return Ok((window, false));
+    }
+
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == "settings")
+        .ok_or_else(|| "settings window is not configured".to_string())?;
+    tracing::warn!("settings window was destroyed; recreating it");
+    let window = tauri::WebviewWindowBuilder::from_config(app, config)
+        .and_then(|builder| builder.build())
+        .map_err(|error| format!("failed to recreate settings window: {error}"))?;
+    Ok((window, true))
 }
 
 fn set_click_through_internal(
@@ -118,7 +448,7 @@ fn set_click_through_internal(
     state: &UiState,
     enabled: bool,
 ) -> Result<bool, String> {
-    let window = main_window(app)?;
+    let window = main_window(app, "set_click_through")?;
     window
         .set_ignore_cursor_events(enabled)
         .map_err(|error| error.to_string())?;
@@ -140,8 +470,84 @@ fn set_snap_internal(app: &AppHandle, state: &UiState, enabled: bool) -> Result<
     Ok(enabled)
 }
 
+fn set_do_not_disturb_internal(
+    app: &AppHandle,
+    state: &UiState,
+    enabled: bool,
+) -> Result<bool, String> {
+    app.state::<SharedInputListenerState>()
+        .set_suppressed(enabled);
+    state.do_not_disturb.store(enabled, Ordering::SeqCst);
+    let _ = app.emit("dnd-changed", DoNotDisturbPayload { enabled });
+    persist::save_value(app, DO_NOT_DISTURB_KEY, &enabled)?;
+    Ok(enabled)
+}
+
+fn set_ghost_mode_internal(
+    app: &AppHandle,
+    state: &UiState,
+    enabled: bool,
+) -> Result<bool, String> {
+    if state.ghost_mode.load(Ordering::SeqCst) == enabled {
+        return Ok(enabled);
+    }
+
+    let mut restore = state
+        .ghost_restore
+        .lock()
+        .map_err(|_| "ghost mode state unavailable".to_string())?;
+    let target = if enabled {
+        *restore = Some(GhostRestore {
+            click_through: state.click_through.load(Ordering::SeqCst),
+            locked: state.locked.load(Ordering::SeqCst),
+        });
+        GhostRestore {
+            click_through: true,
+            locked: true,
+        }
+    } else {
+        restore.take().unwrap_or(GhostRestore {
+            click_through: false,
+            locked: state.locked.load(Ordering::SeqCst),
+        })
+    };
+
+    set_click_through_internal(app, state, target.click_through)?;
+    set_locked_internal(app, state, target.locked)?;
+    state.ghost_mode.store(enabled, Ordering::SeqCst);
+
+    let opacity = if enabled { GHOST_OPACITY } else { 1.0 };
+    let _ = app.emit("ghost-mode-changed", GhostModePayload { enabled, opacity });
+    Ok(enabled)
+}
+
+/// Pushes the OS-level window flags tracked in `UiState` back onto the main
+/// window. They are per-window, so a recreated window would otherwise lose them.
+/// The ghost-mode opacity lives in the renderer, so it is re-sent as well.
+fn reapply_window_flags_internal(app: &AppHandle, state: &UiState) -> Result<(), String> {
+    let window = main_window(app, "reapply_window_flags")?;
+    window
+        .set_ignore_cursor_events(state.click_through.load(Ordering::SeqCst))
+        .map_err(|error| error.to_string())?;
+    window
+        .set_always_on_top(state.always_on_top.load(Ordering::SeqCst))
+        .map_err(|error| error.to_string())?;
+    let enabled = state.ghost_mode.load(Ordering::SeqCst);
+    let opacity = if enabled { GHOST_OPACITY } else { 1.0 };
+    let _ = app.emit("ghost-mode-changed", GhostModePayload { enabled, opacity });
+    Ok(())
+}
+
+fn reapply_window_flags_logged(app: &AppHandle, reason: &str) {
+    let state = app.state::<UiState>();
+    if let Err(error) = reapply_window_flags_internal(app, &state) {
+        tracing::error!("failed to reapply window flags after {reason}: {error}");
+        record_backend_error(app, format!("reapply window flags failed: {error}"));
+    }
+}
+
 fn toggle_main_window_visibility(app: &AppHandle) -> Result<bool, String> {
-    let window = main_window(app)?;
+    let window = main_window(app, "toggle_visibility")?;
     let visible = window.is_visible().map_err(|error| error.to_string())?;
     if visible {
         window.hide().map_err(|error| error.to_string())?;
@@ -149,27 +555,100 @@ fn toggle_main_window_visibility(app: &AppHandle) -> Result<bool, String> {
     }
 
     window.show().map_err(|error| error.to_string())?;
+    reapply_window_flags_logged(app, "show");
     let _ = window.set_focus();
     Ok(true)
 }
 
-fn open_settings_window(app: &AppHandle) -> Result<(), String> {
-    let window = settings_window(app)?;
+/// Shows the settings window and, for a known `section`, asks it to scroll there.
+/// Unknown sections still open the window, just without navigating. Async
+/// because recreating a destroyed window from a sync command can deadlock.
+#[tauri::command(async)]
+fn open_settings_window(app: AppHandle, section: Option<String>) -> Result<(), String> {
+    let (window, created) = settings_window_or_create(&app)?;
     window.show().map_err(|error| error.to_string())?;
     let _ = window.unminimize();
     let _ = window.set_focus();
+
+    let Some(section) = section else {
+        return Ok(());
+    };
+    if !SETTINGS_SECTIONS.contains(&section.as_str()) {
+        tracing::warn!("ignoring unknown settings section {section}");
+        return Ok(());
+    }
+    if created {
+        // A fresh page isn't listening yet; `on_page_load` sends it.
+        if let Ok(mut pending) = PENDING_SETTINGS_SECTION.lock() {
+            *pending = Some(section);
+        }
+        return Ok(());
+    }
+    let _ = app.emit_to(
+        "settings",
+        "settings-navigate",
+        SettingsNavigatePayload { section },
+    );
     Ok(())
 }
 
-fn init_tray(app: &tauri::App) -> tauri::Result<()> {
+/// Opens the settings window off the event loop, for tray handlers that run on
+/// it and would deadlock recreating the window inline.
+fn spawn_open_settings_window(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = open_settings_window(app.clone(), None) {
+            tracing::error!("failed to open settings window: {error}");
+            record_backend_error(&app, format!("open settings failed: {error}"));
+        }
+    });
+}
+
+fn build_models_submenu(app: &AppHandle) -> tauri::Result<Submenu<tauri::Wry>> {
+    let submenu = Submenu::new(app, "Models", true)?;
+    let favorites = library::load_favorites(app);
+
+    if favorites.is_empty() {
+        let empty = MenuItem::new(app, "No favorite models", false, None::<&str>)?;
+        submenu.append(&empty)?;
+    }
+    for path in &favorites {
+        let entry = library::ModelEntry::from_path(path);
+        let item = MenuItem::with_id(
+            app,
+            format!("{MENU_MODEL_PREFIX}{path}"),
+            format!("★ {}", entry.name),
+            true,
+            None::<&str>,
+        )?;
+        submenu.append(&item)?;
+    }
+
+    let recent: Vec<_> = library::load_recent_models(app)
+        .into_iter()
+        .filter(|entry| !favorites.contains(&entry.path))
+        .collect();
+    if !recent.is_empty() {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+    for entry in &recent {
+        let item = MenuItem::with_id(
+            app,
+            format!("{MENU_MODEL_PREFIX}{}", entry.path),
+            library::ModelEntry::from_path(&entry.path).name,
+            true,
+            None::<&str>,
+        )?;
+        submenu.append(&item)?;
+    }
+
+    Ok(submenu)
+}
+
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
     let show_hide = MenuItem::with_id(app, MENU_SHOW_HIDE, "Show/Hide", true, None::<&str>)?;
-    let open_settings = MenuItem::with_id(
-        app,
-        MENU_OPEN_SETTINGS,
-        "Open Settings",
-        true,
-        None::<&str>,
-    )?;
+    let open_settings =
+        MenuItem::with_id(app, MENU_OPEN_SETTINGS, "Open Settings", true, None::<&str>)?;
     let toggle_click_through = MenuItem::with_id(
         app,
         MENU_TOGGLE_CLICK_THROUGH,
@@ -179,11 +658,14 @@ fn init_tray(app: &tauri::App) -> tauri::Result<()> {
     )?;
     let toggle_lock =
         MenuItem::with_id(app, MENU_TOGGLE_LOCK, "Lock / Unlock", true, None::<&str>)?;
-    let toggle_snap =
-        MenuItem::with_id(app, MENU_TOGGLE_SNAP, "Snap Toggle", true, None::<&str>)?;
+    let toggle_snap = MenuItem::with_id(app, MENU_TOGGLE_SNAP, "Snap Toggle", true, None::<&str>)?;
+    let toggle_ghost = MenuItem::with_id(app, MENU_TOGGLE_GHOST, "Ghost Mode", true, None::<&str>)?;
+    let toggle_dnd = MenuItem::with_id(app, MENU_TOGGLE_DND, "Do Not Disturb", true, None::<&str>)?;
+    let models = build_models_submenu(app)?;
+    let separator = PredefinedMenuItem::separator(app)?;
     let quit = MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?;
 
-    let menu = Menu::with_items(
+    Menu::with_items(
         app,
         &[
             &show_hide,
@@ -191,11 +673,57 @@ fn init_tray(app: &tauri::App) -> tauri::Result<()> {
             &toggle_click_through,
             &toggle_lock,
             &toggle_snap,
+            &toggle_ghost,
+            &toggle_dnd,
+            &models,
+            &separator,
             &quit,
         ],
-    )?;
+    )
+}
 
-    let mut builder = TrayIconBuilder::with_id("pet-tray").menu(&menu);
+pub(crate) fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let result = build_tray_menu(app).and_then(|menu| tray.set_menu(Some(menu)));
+    if let Err(error) = result {
+        tracing::error!("failed to rebuild tray menu: {error}");
+        record_backend_error(app, format!("rebuild tray menu failed: {error}"));
+    }
+}
+
+fn tray_double_click_action(app: &AppHandle) -> String {
+    persist::load_value::<String>(app, TRAY_DOUBLE_CLICK_KEY)
+        .filter(|action| TRAY_DOUBLE_CLICK_ACTIONS.contains(&action.as_str()))
+        .unwrap_or_else(|| TRAY_DOUBLE_CLICK_ACTIONS[0].to_string())
+}
+
+fn run_tray_double_click_action(app: &AppHandle) {
+    let action = tray_double_click_action(app);
+    let result = match action.as_str() {
+        "open_settings" => {
+            spawn_open_settings_window(app);
+            Ok(())
+        }
+        "toggle_lock" => {
+            let state = app.state::<UiState>();
+            let next = !state.locked.load(Ordering::SeqCst);
+            set_locked_internal(app, &state, next).map(|_| ())
+        }
+        _ => toggle_main_window_visibility(app).map(|_| ()),
+    };
+
+    if let Err(error) = result {
+        tracing::error!("tray double-click action {action} failed: {error}");
+        record_backend_error(app, format!("tray double-click {action} failed: {error}"));
+    }
+}
+
+fn init_tray(app: &tauri::App) -> tauri::Result<()> {
+    let menu = build_tray_menu(app.handle())?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID).menu(&menu);
     if let Some(icon) = app.default_window_icon() {
         builder = builder.icon(icon.clone());
     }
@@ -208,18 +736,16 @@ fn init_tray(app: &tauri::App) -> tauri::Result<()> {
                     record_backend_error(app_handle, format!("toggle visibility failed: {error}"));
                 }
             }
-            MENU_OPEN_SETTINGS => {
-                if let Err(error) = open_settings_window(app_handle) {
-                    tracing::error!("failed to open settings window: {error}");
-                    record_backend_error(app_handle, format!("open settings failed: {error}"));
-                }
-            }
+            MENU_OPEN_SETTINGS => spawn_open_settings_window(app_handle),
             MENU_TOGGLE_CLICK_THROUGH => {
                 let state = app_handle.state::<UiState>();
                 let next = !state.click_through.load(Ordering::SeqCst);
                 if let Err(error) = set_click_through_internal(app_handle, &state, next) {
                     tracing::error!("failed to toggle click-through from tray: {error}");
-                    record_backend_error(app_handle, format!("toggle click-through failed: {error}"));
+                    record_backend_error(
+                        app_handle,
+                        format!("toggle click-through failed: {error}"),
+                    );
                 }
             }
             MENU_TOGGLE_LOCK => {
@@ -238,18 +764,75 @@ fn init_tray(app: &tauri::App) -> tauri::Result<()> {
                     record_backend_error(app_handle, format!("toggle snap failed: {error}"));
                 }
             }
+            MENU_TOGGLE_GHOST => {
+                let state = app_handle.state::<UiState>();
+                let next = !state.ghost_mode.load(Ordering::SeqCst);
+                if let Err(error) = set_ghost_mode_internal(app_handle, &state, next) {
+                    tracing::error!("failed to toggle ghost mode from tray: {error}");
+                    record_backend_error(app_handle, format!("toggle ghost mode failed: {error}"));
+                }
+            }
+            MENU_TOGGLE_DND => {
+                let state = app_handle.state::<UiState>();
+                let next = !state.do_not_disturb.load(Ordering::SeqCst);
+                if let Err(error) = set_do_not_disturb_internal(app_handle, &state, next) {
+                    tracing::error!("failed to toggle do-not-disturb from tray: {error}");
+                    record_backend_error(
+                        app_handle,
+                        format!("toggle do-not-disturb failed: {error}"),
+                    );
+                }
+            }
             MENU_QUIT => {
                 let state = app_handle.state::<UiState>();
                 state.quitting.store(true, Ordering::SeqCst);
+                shortcuts::unregister_all_shortcuts(app_handle);
                 app_handle.exit(0);
             }
+            id if id.starts_with(MENU_MODEL_PREFIX) => {
+                let path = &id[MENU_MODEL_PREFIX.len()..];
+                if let Err(error) = library::set_active_model_internal(app_handle, path) {
+                    tracing::error!("failed to switch model from tray: {error}");
+                    record_backend_error(app_handle, format!("switch model failed: {error}"));
+                }
+            }
             _ => {}
         })
+        // Only Windows delivers `DoubleClick`; elsewhere this simply never fires.
+        // Single clicks keep the platform default of opening the menu.
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::DoubleClick {
+                button: MouseButton::Left,
+                ..
+            } = event
+            {
+                run_tray_double_click_action(tray.app_handle());
+            }
+        })
         .build(app)?;
 
     Ok(())
 }
 
+#[tauri::command]
+fn get_tray_double_click_action(app: AppHandle) -> String {
+    tray_double_click_action(&app)
+}
+
+/// Chooses what double-clicking the tray icon does (Windows only):
+/// `"toggle_visibility"` (default), `"open_settings"` or `"toggle_lock"`.
+#[tauri::command]
+fn set_tray_double_click_action(app: AppHandle, action: String) -> Result<String, String> {
+    if !TRAY_DOUBLE_CLICK_ACTIONS.contains(&action.as_str()) {
+        return Err(format!(
+            "unknown tray action {action}; expected one of {}",
+            TRAY_DOUBLE_CLICK_ACTIONS.join(", ")
+        ));
+    }
+    persist::save_value(&app, TRAY_DOUBLE_CLICK_KEY, &action)?;
+    Ok(action)
+}
+
 #[tauri::command]
 fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
@@ -311,6 +894,188 @@ fn toggle_snap_enabled(app: AppHandle, state: State<'_, UiState>) -> Result<bool
     set_snap_internal(&app, &state, next)
 }
 
+#[tauri::command]
+fn get_do_not_disturb(state: State<'_, UiState>) -> bool {
+    state.do_not_disturb.load(Ordering::SeqCst)
+}
+
+#[tauri::command]
+fn set_do_not_disturb(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    enabled: bool,
+) -> Result<bool, String> {
+    set_do_not_disturb_internal(&app, &state, enabled)
+}
+
+#[tauri::command]
+fn get_ghost_mode(state: State<'_, UiState>) -> bool {
+    state.ghost_mode.load(Ordering::SeqCst)
+}
+
+#[tauri::command]
+fn set_ghost_mode(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    enabled: bool,
+) -> Result<bool, String> {
+    set_ghost_mode_internal(&app, &state, enabled)
+}
+
+/// Called by a window after it persisted `keys` to the store. Re-applies the
+/// keys the backend owns, then tells every window which keys changed.
+#[tauri::command]
+fn settings_saved(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    keys: Vec<String>,
+) -> Result<(), String> {
+    for key in &keys {
+        match key.as_str() {
+            WINDOW_LOCKED_KEY => {
+                if let Some(locked) = persist::load_value(&app, key) {
+                    set_locked_internal(&app, &state, locked)?;
+                }
+            }
+            SNAP_ENABLED_KEY => {
+                if let Some(enabled) = persist::load_value(&app, key) {
+                    set_snap_internal(&app, &state, enabled)?;
+                }
+            }
+            DO_NOT_DISTURB_KEY => {
+                if let Some(enabled) = persist::load_value(&app, key) {
+                    set_do_not_disturb_internal(&app, &state, enabled)?;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if keys
+        .iter()
+        .any(|key| PET_SETTINGS_KEYS.contains(&key.as_str()))
+    {
+        library::emit_pet_settings_updated(&app);
+    }
+
+    app.emit("settings-changed", SettingsChangedPayload { keys })
+        .map_err(|error| error.to_string())
+}
+
+/// Clears the store except for `keep`, then puts the backend-owned state back
+/// to what a fresh install would load, emitting each change. With `confirm`,
+/// asks first and returns `false` if the user declines.
+#[tauri::command(async)]
+fn reset_settings(
+    app: AppHandle,
+    keep: Option<Vec<String>>,
+    confirm: Option<bool>,
+) -> Result<bool, String> {
+    if confirm.unwrap_or(false) {
+        let confirmed = app
+            .dialog()
+            .message("Reset all settings to their defaults? This cannot be undone.")
+            .title("Reset settings")
+            .kind(MessageDialogKind::Warning)
+            .buttons(MessageDialogButtons::OkCancel)
+            .blocking_show();
+        if !confirmed {
+            return Ok(false);
+        }
+    }
+
+    let keys = persist::clear_except(&app, &keep.unwrap_or_default())?;
+    tracing::info!("reset settings, cleared {} keys", keys.len());
+    reapply_stored_settings(&app, "settings reset")?;
+    app.emit("settings-changed", SettingsChangedPayload { keys })
+        .map_err(|error| error.to_string())?;
+    Ok(true)
+}
+
+/// Shows a "Pet is alive!" message so users can check that the app can reach
+/// them. The notification plugin is not wired in, so this uses the dialog
+/// plugin as the fallback: a native message box that returns once dismissed.
+/// Fails when the dialog plugin did not initialize.
+#[tauri::command(async)]
+fn send_test_notification(app: AppHandle) -> Result<(), String> {
+    if let Some(error) = plugin_status::plugin_error("dialog") {
+        let message = format!("cannot show the test notification: {error}");
+        app.state::<SharedDiagnosticsState>().record_error(
+            "error".to_string(),
+            message.clone(),
+            Some("dialog".to_string()),
+        );
+        return Err(message);
+    }
+
+    app.dialog()
+        .message("Pet is alive!")
+        .title(app.package_info().name.clone())
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::Ok)
+        .blocking_show();
+
+    tracing::info!("test notification shown as a dialog");
+    app.state::<SharedDiagnosticsState>().record_note(
+        "test notification shown as a dialog".to_string(),
+        Some("dialog".to_string()),
+    );
+    Ok(())
+}
+
+/// Puts the backend-owned state back to what the store holds, falling back to
+/// fresh-install defaults for missing keys, and emits each change. Used after
+/// the store is replaced wholesale.
+fn reapply_stored_settings(app: &AppHandle, reason: &str) -> Result<(), String> {
+    let state = app.state::<UiState>();
+    set_ghost_mode_internal(app, &state, false)?;
+    let defaults = UiState::default();
+    let load_bool = |key: &str, default: &AtomicBool| {
+        persist::load_value(app, key).unwrap_or(default.load(Ordering::SeqCst))
+    };
+    set_click_through_internal(app, &state, defaults.click_through.load(Ordering::SeqCst))?;
+    set_locked_internal(app, &state, load_bool(WINDOW_LOCKED_KEY, &defaults.locked))?;
+    let snap_enabled = load_bool(SNAP_ENABLED_KEY, &defaults.snap_enabled);
+    set_snap_internal(app, &state, snap_enabled)?;
+    let do_not_disturb = load_bool(DO_NOT_DISTURB_KEY, &defaults.do_not_disturb);
+    set_do_not_disturb_internal(app, &state, do_not_disturb)?;
+    let always_on_top = defaults.always_on_top.load(Ordering::SeqCst);
+    state.always_on_top.store(always_on_top, Ordering::SeqCst);
+    reapply_window_flags_logged(app, reason);
+    input_listener::restore_capture_scope(app);
+    input_listener::restore_idle_threshold(app);
+    input_listener::restore_channel_capacity(app);
+    let listener = app.state::<SharedInputListenerState>();
+    let tuning_before = listener.config();
+    listener.reset_tuning()?;
+    presets::restore_tuning_preset(app);
+    input_listener::emit_tuning_changes(app, &tuning_before, &listener.config());
+    reaction_cooldown::restore_reaction_cooldown(app);
+    shortcuts::unregister_all_shortcuts(app);
+    shortcuts::restore_shortcuts(app);
+    updates::restore_update_schedule(app);
+    tray_icon::restore_tray_icon(app);
+
+    let diagnostics = app.state::<SharedDiagnosticsState>();
+    let bounds = MetricBounds::default();
+    diagnostics.set_metric_bounds(bounds.max_fps, bounds.max_load_ms)?;
+    diagnostics.set_input_recording(persist::load_value(app, INPUT_RECORDING_KEY).unwrap_or(true));
+    let anonymization: Option<String> = persist::load_value(app, INPUT_ANONYMIZATION_KEY);
+    diagnostics.set_input_anonymization(
+        anonymization
+            .and_then(|level| InputAnonymization::parse(&level).ok())
+            .unwrap_or(InputAnonymization::None),
+    );
+
+    library::emit_pet_settings_updated(app);
+    Ok(())
+}
+
+#[tauri::command]
+fn reapply_window_flags(app: AppHandle, state: State<'_, UiState>) -> Result<(), String> {
+    reapply_window_flags_internal(&app, &state)
+}
+
 #[tauri::command]
 fn log_frontend_error(
     diagnostics: State<'_, SharedDiagnosticsState>,
@@ -343,18 +1108,258 @@ fn report_runtime_metrics(
     diagnostics.set_metrics(fps, model_load_ms);
 }
 
+fn debug_metrics_allowed() -> Result<(), String> {
+    static ALLOWED: Lazy<bool> = Lazy::new(|| {
+        cfg!(debug_assertions)
+            || std::env::var("ALLOW_DEBUG_METRICS")
+                .map(|value| matches!(value.as_str(), "1" | "true"))
+                .unwrap_or(false)
+    });
+
+    if *ALLOWED {
+        Ok(())
+    } else {
+        Err("debug metrics are disabled; set ALLOW_DEBUG_METRICS=1 to enable them".to_string())
+    }
+}
+
+#[tauri::command]
+fn set_fps(diagnostics: State<'_, SharedDiagnosticsState>, value: f64) -> Result<(), String> {
+    debug_metrics_allowed()?;
+    diagnostics.set_metrics(Some(value), None);
+    Ok(())
+}
+
 #[tauri::command]
-fn get_diagnostics_snapshot(
+fn set_model_load_ms(
     diagnostics: State<'_, SharedDiagnosticsState>,
-) -> DiagnosticsSnapshot {
+    value: f64,
+) -> Result<(), String> {
+    debug_metrics_allowed()?;
+    diagnostics.set_metrics(None, Some(value));
+    Ok(())
+}
+
+/// Raises or lowers the clamps on reported FPS and model load time, e.g. for
+/// displays that legitimately render above 1000 FPS.
+#[tauri::command]
+fn set_metric_bounds(
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    max_fps: f64,
+    max_load_ms: f64,
+) -> Result<MetricBounds, String> {
+    diagnostics.set_metric_bounds(max_fps, max_load_ms)
+}
+
+/// Holds FPS and model load time at their current values, e.g. for a stable
+/// screenshot during a benchmark, until called again with `false`.
+#[tauri::command]
+fn set_metrics_frozen(diagnostics: State<'_, SharedDiagnosticsState>, frozen: bool) {
+    diagnostics.set_metrics_frozen(frozen);
+}
+
+#[tauri::command]
+fn get_diagnostics_snapshot(diagnostics: State<'_, SharedDiagnosticsState>) -> DiagnosticsSnapshot {
     diagnostics.snapshot()
 }
 
+/// Recent errors tagged with `context`; pass `"(none)"` for untagged ones.
+#[tauri::command]
+fn get_errors_by_context(
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    context: String,
+    limit: usize,
+) -> Vec<DiagnosticErrorRecord> {
+    diagnostics.errors_by_context(&context, limit)
+}
+
+/// Counts of each input event type over the last `window_ms`, from the
+/// diagnostics buffer (so at most its last 50 events).
+#[tauri::command]
+fn input_event_histogram(
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    window_ms: u64,
+) -> HashMap<String, u64> {
+    diagnostics.input_event_histogram(window_ms, diagnostics::now_timestamp_ms())
+}
+
+#[tauri::command]
+fn get_input_recording(diagnostics: State<'_, SharedDiagnosticsState>) -> bool {
+    diagnostics.input_recording()
+}
+
+/// Controls whether forwarded input events are also kept in the diagnostics
+/// buffer. Live `global-input` events are unaffected.
+#[tauri::command]
+fn set_input_recording(
+    app: AppHandle,
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    enabled: bool,
+) -> Result<bool, String> {
+    diagnostics.set_input_recording(enabled);
+    persist::save_value(&app, INPUT_RECORDING_KEY, &enabled)?;
+    Ok(enabled)
+}
+
+#[tauri::command]
+fn get_input_anonymization(diagnostics: State<'_, SharedDiagnosticsState>) -> String {
+    diagnostics.input_anonymization().as_str().to_string()
+}
+
+/// Sets how recorded input events are redacted: `"none"`, `"keys"` (key codes
+/// become `"key"`) or `"full"` (also drops x/y/distance).
+#[tauri::command]
+fn set_input_anonymization(
+    app: AppHandle,
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    level: String,
+) -> Result<String, String> {
+    let level = InputAnonymization::parse(&level)?;
+    diagnostics.set_input_anonymization(level);
+    persist::save_value(&app, INPUT_ANONYMIZATION_KEY, &level.as_str())?;
+    Ok(level.as_str().to_string())
+}
+
+#[tauri::command]
+fn get_log_paths() -> Result<LogPaths, String> {
+    let log_dir = LOG_DIR
+        .get()
+        .ok_or_else(|| "file logging is not initialized".to_string())?;
+
+    Ok(LogPaths {
+        log_dir: log_dir.to_string_lossy().to_string(),
+        current_log: current_log_file(log_dir).map(|path| path.to_string_lossy().to_string()),
+        error_log: ERROR_LOG_GUARD
+            .get()
+            .and_then(|_| active_log_file(log_dir, ERROR_LOG_PREFIX))
+            .map(|path| path.to_string_lossy().to_string()),
+    })
+}
+
+fn unix_ms(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Size and age of the daily main and error log files, for showing before
+/// offering cleanup.
+#[tauri::command]
+fn get_log_dir_stats() -> Result<LogDirStats, String> {
+    let log_dir = LOG_DIR
+        .get()
+        .ok_or_else(|| "file logging is not initialized".to_string())?;
+    let files = rolling_log_files(log_dir);
+    let modified_ms = || files.iter().map(|(_, _, modified)| unix_ms(*modified));
+
+    Ok(LogDirStats {
+        file_count: files.len(),
+        total_bytes: files.iter().map(|(_, len, _)| len).sum(),
+        oldest_ms: modified_ms().min(),
+        newest_ms: modified_ms().max(),
+    })
+}
+
+/// Deletes daily log files last written more than `max_age_days` ago, never
+/// the active main or error log. Returns the number of bytes freed.
+#[tauri::command(async)]
+fn clear_old_logs(max_age_days: u64) -> Result<u64, String> {
+    let log_dir = LOG_DIR
+        .get()
+        .ok_or_else(|| "file logging is not initialized".to_string())?;
+    let max_age = std::time::Duration::from_secs(max_age_days.saturating_mul(24 * 60 * 60));
+    let cutoff = std::time::SystemTime::now()
+        .checked_sub(max_age)
+        .unwrap_or(std::time::UNIX_EPOCH);
+    let active = [
+        current_log_file(log_dir),
+        active_log_file(log_dir, ERROR_LOG_PREFIX),
+    ];
+
+    let mut freed = 0;
+    for (path, len, modified) in rolling_log_files(log_dir) {
+        if modified >= cutoff || active.contains(&Some(path.clone())) {
+            continue;
+        }
+        match std::fs::remove_file(&path) {
+            Ok(()) => freed += len,
+            Err(error) => tracing::warn!("failed to delete log {}: {error}", path.display()),
+        }
+    }
+    Ok(freed)
+}
+
+/// Returns the last `lines` lines of the active daily log (older rotated files
+/// are not read). Lines logged in the last few milliseconds may still be queued
+/// in the non-blocking writer and be missing from the result.
+#[tauri::command(async)]
+fn read_recent_logs(lines: usize) -> Result<Vec<String>, String> {
+    let log_dir = LOG_DIR
+        .get()
+        .ok_or_else(|| "file logging is not initialized".to_string())?;
+    std::thread::sleep(LOG_DRAIN_WAIT);
+
+    let Some(path) = current_log_file(log_dir) else {
+        return Ok(Vec::new());
+    };
+    log_tail::read_tail_lines(&path, lines.min(MAX_TAIL_LOG_LINES))
+        .map_err(|error| format!("failed to read {}: {error}", path.display()))
+}
+
+/// Opens the folder holding the settings store in the system file manager,
+/// creating it first on a fresh install.
+#[tauri::command]
+fn open_data_dir(app: AppHandle) -> Result<(), String> {
+    let data_dir = storage_root::data_dir(&app)?;
+    std::fs::create_dir_all(&data_dir)
+        .map_err(|error| format!("failed to create {}: {error}", data_dir.display()))?;
+    app.opener()
+        .open_path(data_dir.to_string_lossy(), None::<&str>)
+        .map_err(|error| format!("failed to open {}: {error}", data_dir.display()))
+}
+
+/// Recent main and settings window events, oldest first.
+#[tauri::command]
+fn get_window_event_log(diagnostics: State<'_, SharedDiagnosticsState>) -> Vec<WindowEventRecord> {
+    diagnostics.window_events()
+}
+
+/// Diagnostics as Markdown for pasting into an issue; see `diagnostics_markdown`.
+#[tauri::command]
+fn diagnostics_markdown(app: AppHandle, diagnostics: State<'_, SharedDiagnosticsState>) -> String {
+    let package = app.package_info();
+    diagnostics::diagnostics_markdown(
+        &diagnostics.snapshot(),
+        &package.name,
+        &package.version.to_string(),
+    )
+}
+
+#[tauri::command]
+fn export_input_events_csv(
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    dest: String,
+) -> Result<String, String> {
+    let dest = std::path::PathBuf::from(dest);
+    if let Some(parent) = dest
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .map_err(|error| format!("failed to create {}: {error}", parent.display()))?;
+    }
+
+    let csv = diagnostics::input_events_csv(&diagnostics.snapshot().input_events);
+    std::fs::write(&dest, csv)
+        .map_err(|error| format!("failed to write {}: {error}", dest.display()))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
 #[tauri::command]
 fn open_input_monitoring_settings() -> Result<(), String> {
     #[cfg(target_os = "macos")]
     {
-        Command::new("open")
+        std::process::Command::new("open")
             .arg("x-apple.systempreferences:com.apple.preference.security?Privacy_ListenEvent")
             .status()
             .map_err(|error| format!("failed to open System Settings: {error}"))?;
@@ -373,8 +1378,15 @@ pub fn run() {
         .manage(UiState::default())
         .manage(Arc::new(InputListenerState::default()))
         .manage(Arc::new(DiagnosticsState::default()))
+        .manage(ShortcutBindings::default())
+        .manage(drag::DragState::default())
         .plugin(tauri_plugin_autostart::Builder::new().build())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(shortcuts::handle_shortcut_event)
+                .build(),
+        )
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
@@ -385,16 +1397,91 @@ pub fn run() {
                 tracing::info!("logging initialized");
             }
 
+            plugin_status::probe_plugins(app.handle());
             init_tray(app)?;
+            tray_icon::restore_tray_icon(app.handle());
+            model_scan::init_scan_allowlist(app.handle());
+            window_placement::spawn_monitor_watcher(app.handle().clone());
+            window_placement::remember_window_geometry(app.handle());
+            permissions::spawn_permissions_watcher(app.handle().clone());
+            system_idle::spawn_system_idle_watcher(app.handle().clone());
+            window_reactions::listen_for_active_window(app.handle());
+            active_window::spawn_active_window_watcher(app.handle().clone());
+            updates::restore_update_schedule(app.handle());
+            session_info::note_remote_session(app.handle());
+            shortcuts::restore_shortcuts(app.handle());
 
             let state = app.state::<UiState>();
             if let Err(error) = set_click_through_internal(app.handle(), &state, false) {
                 tracing::error!("failed to initialize click-through state: {error}");
                 record_backend_error(app.handle(), format!("init click-through failed: {error}"));
             }
+
+            let do_not_disturb =
+                persist::load_value(app.handle(), DO_NOT_DISTURB_KEY).unwrap_or(false);
+            if let Err(error) = set_do_not_disturb_internal(app.handle(), &state, do_not_disturb) {
+                tracing::error!("failed to restore do-not-disturb state: {error}");
+                record_backend_error(app.handle(), format!("init do-not-disturb failed: {error}"));
+            }
+
+            input_listener::restore_capture_scope(app.handle());
+            input_listener::restore_idle_threshold(app.handle());
+            input_listener::restore_channel_capacity(app.handle());
+            presets::restore_tuning_preset(app.handle());
+            reaction_cooldown::restore_reaction_cooldown(app.handle());
+            let diagnostics = app.state::<SharedDiagnosticsState>();
+            if let Some(enabled) = persist::load_value(app.handle(), INPUT_RECORDING_KEY) {
+                diagnostics.set_input_recording(enabled);
+            }
+            let saved_anonymization: Option<String> =
+                persist::load_value(app.handle(), INPUT_ANONYMIZATION_KEY);
+            if let Some(level) =
+                saved_anonymization.and_then(|level| InputAnonymization::parse(&level).ok())
+            {
+                diagnostics.set_input_anonymization(level);
+            }
             Ok(())
         })
+        .on_page_load(|webview, payload| {
+            if webview.label() == "main" && payload.event() == PageLoadEvent::Finished {
+                reapply_window_flags_logged(webview.app_handle(), "page load");
+            }
+            if webview.label() == "settings" && payload.event() == PageLoadEvent::Finished {
+                let section = PENDING_SETTINGS_SECTION
+                    .lock()
+                    .ok()
+                    .and_then(|mut pending| pending.take());
+                if let Some(section) = section {
+                    let payload = SettingsNavigatePayload { section };
+                    let _ = webview
+                        .app_handle()
+                        .emit_to("settings", "settings-navigate", payload);
+                }
+            }
+        })
         .on_window_event(|window, event| {
+            record_window_event(window, event);
+            if window.label() == "main" {
+                match event {
+                    WindowEvent::ScaleFactorChanged { .. } => {
+                        reapply_window_flags_logged(window.app_handle(), "display change");
+                        window_placement::reapply_last_anchor(window.app_handle());
+                    }
+                    WindowEvent::Moved(position) => {
+                        window_placement::remember_window_geometry(window.app_handle());
+                        drag::record_window_moved(window.app_handle(), *position);
+                    }
+                    WindowEvent::Resized(size) => {
+                        window_placement::remember_window_geometry(window.app_handle());
+                        window_placement::record_window_resized(window.app_handle(), *size);
+                    }
+                    WindowEvent::Focused(focused) => {
+                        window_placement::record_focus_changed(window.app_handle(), *focused);
+                    }
+                    _ => {}
+                }
+            }
+
             if let WindowEvent::CloseRequested { api, .. } = event {
                 let app = window.app_handle();
                 let state = app.state::<UiState>();
@@ -402,16 +1489,70 @@ pub fn run() {
                     api.prevent_close();
                     if let Err(error) = window.hide() {
                         tracing::error!("failed to hide window on close request: {error}");
-                        record_backend_error(&app, format!("hide window on close failed: {error}"));
+                        record_backend_error(app, format!("hide window on close failed: {error}"));
                     }
                 }
             }
         })
         .invoke_handler(tauri::generate_handler![
             greet,
+            get_tray_double_click_action,
+            set_tray_double_click_action,
             start_listener,
             stop_listener,
+            run_input_self_test,
+            set_frontend_emit,
+            set_event_trace,
+            get_input_batching,
+            set_input_batching,
+            start_recording_session,
+            stop_recording_session,
+            replay_session,
+            get_input_channel_capacity,
+            set_input_channel_capacity,
+            set_throttle_for,
+            set_adaptive_throttle,
+            get_forwarder_poll_ms,
+            get_held_inputs,
+            get_thread_health,
+            get_capture_scope,
+            set_capture_scope,
+            get_idle_threshold_ms,
+            set_idle_threshold_ms,
+            list_presets,
+            apply_preset,
+            get_fps_cap,
+            verify_pending_update,
+            install_pending_update,
+            check_for_update,
+            set_update_check_interval,
+            get_last_update_check,
+            set_forwarder_poll_ms,
             find_model3_json,
+            find_all_model3_json,
+            search_models,
+            list_active_scans,
+            cancel_all_scans,
+            get_library_roots,
+            add_library_root,
+            remove_library_root,
+            model_fingerprint,
+            find_duplicate_models,
+            locate_moved_model,
+            warm_model,
+            model_disk_size,
+            benchmark_model_parse,
+            model_canvas_size,
+            extract_model,
+            pick_and_validate_model,
+            list_model_animations,
+            list_animations_in_dir,
+            trigger_motion,
+            random_motion,
+            get_reaction_cooldown_ms,
+            set_reaction_cooldown_ms,
+            set_texture_warning_threshold,
+            set_model3_max_bytes,
             get_click_through,
             set_click_through,
             toggle_click_through,
@@ -421,10 +1562,82 @@ pub fn run() {
             get_snap_enabled,
             set_snap_enabled,
             toggle_snap_enabled,
+            snap_to,
+            nudge_pet,
+            set_relative_anchor,
+            center_pet,
+            flash_over_fullscreen,
+            set_focus_debounce_ms,
+            get_window_reaction_rules,
+            set_window_reaction_rules,
+            get_snap_config,
+            set_snap_config,
+            get_monitors,
+            get_window_geometry,
+            get_do_not_disturb,
+            set_do_not_disturb,
+            get_ghost_mode,
+            set_ghost_mode,
+            reapply_window_flags,
+            settings_saved,
             log_frontend_error,
             report_runtime_metrics,
+            set_fps,
+            set_model_load_ms,
             get_diagnostics_snapshot,
-            open_input_monitoring_settings
+            export_input_events_csv,
+            diagnostics_markdown,
+            get_window_event_log,
+            get_errors_by_context,
+            input_event_histogram,
+            get_effective_config,
+            start_window_drag,
+            set_metric_bounds,
+            set_metrics_frozen,
+            get_input_recording,
+            set_input_recording,
+            get_input_anonymization,
+            set_input_anonymization,
+            get_log_paths,
+            export_config,
+            import_config,
+            get_log_dir_stats,
+            clear_old_logs,
+            read_recent_logs,
+            open_data_dir,
+            set_storage_root,
+            get_store_path,
+            reset_settings,
+            send_test_notification,
+            capture_window_state,
+            apply_window_config,
+            restore_window_state,
+            set_tray_icon,
+            reset_tray_icon,
+            get_session_info,
+            get_plugin_status,
+            open_input_monitoring_settings,
+            get_permissions_status,
+            ensure_input_permission,
+            get_system_idle_ms,
+            open_settings_window,
+            get_shortcuts,
+            set_shortcut,
+            save_profile,
+            load_profile,
+            list_profiles,
+            delete_profile,
+            export_profile,
+            import_profile,
+            set_active_model,
+            toggle_favorite,
+            is_favorite,
+            list_favorites,
+            push_recent_model,
+            list_recent_models,
+            clear_recent_models,
+            set_recent_models_limit,
+            relink_model
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
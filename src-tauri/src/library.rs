@@ -0,0 +1,408 @@
+use crate::diagnostics::now_timestamp_ms;
+use crate::model_fingerprint::fingerprint_model;
+use crate::model_scan::{init_scan_allowlist, model_path_exists, resolve_scan_dir};
+use crate::model_validate::{model_canvas_size_internal, validate_model3};
+use crate::persist::{load_value, save_value};
+use crate::profiles::{load_profiles, save_profiles};
+use crate::window_placement::fit_main_window_to_canvas;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+
+const FAVORITES_KEY: &str = "favoriteModels";
+const LIBRARY_ROOTS_KEY: &str = "libraryRoots";
+const MODEL_FINGERPRINTS_KEY: &str = "modelFingerprints";
+const MODEL_PATH_KEY: &str = "modelPath";
+const RECENT_MODELS_KEY: &str = "recentModels";
+const RECENT_MODELS_LIMIT_KEY: &str = "recentModelsLimit";
+const DEFAULT_RECENT_MODELS_LIMIT: usize = 8;
+const MAX_RECENT_MODELS_LIMIT: usize = 50;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelEntry {
+    pub path: String,
+    pub name: String,
+}
+
+impl ModelEntry {
+    pub fn from_path(path: &str) -> Self {
+        let model_path = Path::new(path);
+        // `foo/hiyori/hiyori.model3.json` -> "hiyori"; fall back to the file stem.
+        let name = model_path
+            .parent()
+            .and_then(|parent| parent.file_name())
+            .or_else(|| model_path.file_stem())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string());
+
+        Self {
+            path: path.to_string(),
+            name,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentModel {
+    pub path: String,
+    pub used_at: u64,
+}
+
+/// Re-broadcasts the stored pet settings so the pet window reloads them, the
+/// same way the settings window does after saving.
+pub(crate) fn emit_pet_settings_updated(app: &AppHandle) {
+    let field = |key: &str| load_value::<JsonValue>(app, key).unwrap_or(JsonValue::Null);
+    let payload = json!({
+        "modelPath": field(MODEL_PATH_KEY),
+        "scale": field("scale"),
+        "pos": field("pos"),
+        "motionMap": field("motionMap"),
+    });
+
+    if let Err(error) = app.emit_to("main", "pet-settings-updated", payload) {
+        tracing::warn!("failed to emit pet-settings-updated: {error}");
+    }
+}
+
+/// Folders the library searches by default, stored as canonical paths.
+pub(crate) fn load_library_roots(app: &AppHandle) -> Vec<String> {
+    load_value(app, LIBRARY_ROOTS_KEY).unwrap_or_default()
+}
+
+fn save_library_roots(app: &AppHandle, roots: &[String]) -> Result<(), String> {
+    save_value(app, LIBRARY_ROOTS_KEY, &roots)?;
+    init_scan_allowlist(app);
+    let _ = app.emit("library-roots-changed", roots);
+    Ok(())
+}
+
+/// Runs stored roots through the same checks as `add_library_root`, e.g. after
+/// importing a config from another machine, and drops the ones that fail.
+pub(crate) fn revalidate_library_roots(app: &AppHandle) -> Result<(), String> {
+    let stored = load_library_roots(app);
+    let mut roots: Vec<String> = Vec::new();
+    for root in &stored {
+        match resolve_scan_dir(root) {
+            Ok(path) => {
+                let path = path.to_string_lossy().to_string();
+                if !roots.contains(&path) {
+                    roots.push(path);
+                }
+            }
+            Err(error) => tracing::warn!("dropping library root {root}: {error}"),
+        }
+    }
+    if roots != stored {
+        save_library_roots(app, &roots)?;
+    }
+    Ok(())
+}
+
+/// Fingerprint recorded for a path when it entered recents or favorites.
+pub(crate) fn stored_fingerprint(app: &AppHandle, path: &str) -> Option<String> {
+    load_value::<BTreeMap<String, String>>(app, MODEL_FINGERPRINTS_KEY)?.remove(path)
+}
+
+/// Records `path`'s fingerprint so `locate_moved_model` can find it later.
+/// Bundled and remote models can't be fingerprinted and are skipped.
+fn remember_fingerprint(app: &AppHandle, path: &str) {
+    let fingerprint = match fingerprint_model(Path::new(path)) {
+        Ok(fingerprint) => fingerprint,
+        Err(error) => {
+            tracing::debug!("not fingerprinting {path}: {error}");
+            return;
+        }
+    };
+
+    let mut fingerprints: BTreeMap<String, String> =
+        load_value(app, MODEL_FINGERPRINTS_KEY).unwrap_or_default();
+    if fingerprints.get(path) == Some(&fingerprint) {
+        return;
+    }
+    fingerprints.insert(path.to_string(), fingerprint);
+    if let Err(error) = save_value(app, MODEL_FINGERPRINTS_KEY, &fingerprints) {
+        tracing::warn!("failed to save model fingerprint: {error}");
+    }
+}
+
+pub(crate) fn active_model_path(app: &AppHandle) -> Option<String> {
+    load_value::<String>(app, MODEL_PATH_KEY).filter(|path| !path.trim().is_empty())
+}
+
+pub(crate) fn load_favorites(app: &AppHandle) -> Vec<String> {
+    load_value(app, FAVORITES_KEY).unwrap_or_default()
+}
+
+fn save_favorites(app: &AppHandle, favorites: &[String]) -> Result<(), String> {
+    save_value(app, FAVORITES_KEY, &favorites)?;
+    let entries: Vec<ModelEntry> = favorites
+        .iter()
+        .map(|path| ModelEntry::from_path(path))
+        .collect();
+    let _ = app.emit("favorites-changed", entries);
+    crate::refresh_tray_menu(app);
+    Ok(())
+}
+
+/// Sizes the window to a model the first time it is used. Later switches keep
+/// whatever size the user has since picked.
+fn fit_window_to_model(app: &AppHandle, path: &str) {
+    let result = model_canvas_size_internal(Path::new(path))
+        .and_then(|canvas| fit_main_window_to_canvas(app, &canvas));
+    if let Err(error) = result {
+        tracing::warn!("keeping the window size for {path}: {error}");
+    }
+}
+
+pub(crate) fn set_active_model_internal(app: &AppHandle, path: &str) -> Result<String, String> {
+    let path = path.trim();
+    if !model_path_exists(path) {
+        return Err(format!("Model file does not exist: {path}"));
+    }
+
+    let first_load = !load_recent_models(app)
+        .iter()
+        .any(|entry| entry.path == path);
+    save_value(app, MODEL_PATH_KEY, &path)?;
+    if first_load {
+        fit_window_to_model(app, path);
+    }
+    emit_pet_settings_updated(app);
+    push_recent_model_internal(app, path)?;
+    Ok(path.to_string())
+}
+
+pub(crate) fn load_recent_models(app: &AppHandle) -> Vec<RecentModel> {
+    load_value(app, RECENT_MODELS_KEY).unwrap_or_default()
+}
+
+fn recent_models_limit(app: &AppHandle) -> usize {
+    load_value(app, RECENT_MODELS_LIMIT_KEY).unwrap_or(DEFAULT_RECENT_MODELS_LIMIT)
+}
+
+fn save_recent_models(app: &AppHandle, recent: &[RecentModel]) -> Result<(), String> {
+    save_value(app, RECENT_MODELS_KEY, &recent)?;
+    let _ = app.emit("recent-models-changed", recent);
+    crate::refresh_tray_menu(app);
+    Ok(())
+}
+
+fn push_recent_model_internal(app: &AppHandle, path: &str) -> Result<Vec<RecentModel>, String> {
+    let mut recent = load_recent_models(app);
+    recent.retain(|entry| entry.path != path);
+    recent.insert(
+        0,
+        RecentModel {
+            path: path.to_string(),
+            used_at: now_timestamp_ms(),
+        },
+    );
+    recent.truncate(recent_models_limit(app));
+
+    save_recent_models(app, &recent)?;
+    remember_fingerprint(app, path);
+    Ok(recent)
+}
+
+#[tauri::command]
+pub fn set_active_model(app: AppHandle, path: String) -> Result<String, String> {
+    set_active_model_internal(&app, &path)
+}
+
+#[tauri::command]
+pub fn get_library_roots(app: AppHandle) -> Vec<String> {
+    load_library_roots(&app)
+}
+
+/// Adds a library folder. It must be an existing directory and is added to the
+/// scan allowlist, so folders outside the default scan locations can be used;
+/// adding the same folder by another spelling is a no-op.
+#[tauri::command]
+pub fn add_library_root(app: AppHandle, path: String) -> Result<(), String> {
+    let root = resolve_scan_dir(path.trim())?.to_string_lossy().to_string();
+    let mut roots = load_library_roots(&app);
+    if roots.contains(&root) {
+        return Ok(());
+    }
+    roots.push(root);
+    save_library_roots(&app, &roots)
+}
+
+/// Removes a library folder, matching either the stored path or, while the
+/// folder still exists, its canonical form.
+#[tauri::command]
+pub fn remove_library_root(app: AppHandle, path: String) -> Result<(), String> {
+    let path = path.trim();
+    let canonical = Path::new(path)
+        .canonicalize()
+        .map(|canonical| canonical.to_string_lossy().to_string())
+        .ok();
+
+    let mut roots = load_library_roots(&app);
+    let before = roots.len();
+    roots.retain(|root| root != path && Some(root) != canonical.as_ref());
+    if roots.len() == before {
+        return Ok(());
+    }
+    save_library_roots(&app, &roots)
+}
+
+#[tauri::command]
+pub fn toggle_favorite(app: AppHandle, path: String) -> Result<bool, String> {
+    let path = path.trim().to_string();
+    let mut favorites = load_favorites(&app);
+    let favorite = match favorites.iter().position(|entry| entry == &path) {
+        Some(index) => {
+            favorites.remove(index);
+            false
+        }
+        None => {
+            remember_fingerprint(&app, &path);
+            favorites.push(path);
+            true
+        }
+    };
+
+    save_favorites(&app, &favorites)?;
+    Ok(favorite)
+}
+
+#[tauri::command]
+pub fn is_favorite(app: AppHandle, path: String) -> bool {
+    let path = path.trim();
+    load_favorites(&app).iter().any(|entry| entry == path)
+}
+
+/// Lists favorites, dropping (and forgetting) any whose model file vanished.
+#[tauri::command]
+pub fn list_favorites(app: AppHandle) -> Result<Vec<ModelEntry>, String> {
+    let favorites = load_favorites(&app);
+    let existing: Vec<String> = favorites
+        .iter()
+        .filter(|path| model_path_exists(path))
+        .cloned()
+        .collect();
+
+    if existing.len() != favorites.len() {
+        tracing::info!(
+            "pruned {} missing favorite model(s)",
+            favorites.len() - existing.len()
+        );
+        save_favorites(&app, &existing)?;
+    }
+
+    Ok(existing
+        .iter()
+        .map(|path| ModelEntry::from_path(path))
+        .collect())
+}
+
+#[tauri::command]
+pub fn push_recent_model(app: AppHandle, path: String) -> Result<Vec<RecentModel>, String> {
+    push_recent_model_internal(&app, path.trim())
+}
+
+/// Lists recent models newest first, dropping entries whose file vanished.
+#[tauri::command]
+pub fn list_recent_models(app: AppHandle) -> Result<Vec<RecentModel>, String> {
+    let recent = load_recent_models(&app);
+    let existing: Vec<RecentModel> = recent
+        .iter()
+        .filter(|entry| model_path_exists(&entry.path))
+        .cloned()
+        .collect();
+
+    if existing.len() != recent.len() {
+        save_recent_models(&app, &existing)?;
+    }
+    Ok(existing)
+}
+
+#[tauri::command]
+pub fn clear_recent_models(app: AppHandle) -> Result<(), String> {
+    save_recent_models(&app, &[])
+}
+
+#[tauri::command]
+pub fn set_recent_models_limit(app: AppHandle, limit: usize) -> Result<usize, String> {
+    let limit = limit.clamp(1, MAX_RECENT_MODELS_LIMIT);
+    save_value(&app, RECENT_MODELS_LIMIT_KEY, &limit)?;
+
+    let mut recent = load_recent_models(&app);
+    if recent.len() > limit {
+        recent.truncate(limit);
+        save_recent_models(&app, &recent)?;
+    }
+    Ok(limit)
+}
+
+/// Points every stored reference to `old_path` (active model, recents,
+/// favorites, profiles) at `new_path`, after checking the new model is complete.
+#[tauri::command]
+pub fn relink_model(app: AppHandle, old_path: String, new_path: String) -> Result<(), String> {
+    let old_path = old_path.trim();
+    let new_path = new_path.trim();
+    let validated = validate_model3(Path::new(new_path))?;
+    if !validated.valid {
+        return Err(format!(
+            "{new_path} is not a valid model: {}",
+            validated.summary
+        ));
+    }
+
+    if active_model_path(&app).as_deref() == Some(old_path) {
+        save_value(&app, MODEL_PATH_KEY, &new_path)?;
+        emit_pet_settings_updated(&app);
+    }
+
+    let recent = load_recent_models(&app);
+    if recent.iter().any(|entry| entry.path == old_path) {
+        let mut relinked: Vec<RecentModel> = Vec::with_capacity(recent.len());
+        for mut entry in recent {
+            if entry.path == old_path {
+                entry.path = new_path.to_string();
+            }
+            if !relinked.iter().any(|existing| existing.path == entry.path) {
+                relinked.push(entry);
+            }
+        }
+        save_recent_models(&app, &relinked)?;
+    }
+
+    let favorites = load_favorites(&app);
+    if favorites.iter().any(|path| path == old_path) {
+        let mut relinked: Vec<String> = Vec::with_capacity(favorites.len());
+        for path in favorites {
+            let path = if path == old_path {
+                new_path.to_string()
+            } else {
+                path
+            };
+            if !relinked.contains(&path) {
+                relinked.push(path);
+            }
+        }
+        save_favorites(&app, &relinked)?;
+    }
+
+    let mut profiles = load_profiles(&app);
+    let mut profiles_changed = false;
+    for profile in profiles.values_mut() {
+        if profile.model_path.as_deref() == Some(old_path) {
+            profile.model_path = Some(new_path.to_string());
+            profile.needs_relink = false;
+            profiles_changed = true;
+        }
+    }
+    if profiles_changed {
+        save_profiles(&app, &profiles)?;
+    }
+
+    remember_fingerprint(&app, new_path);
+    tracing::info!("relinked model {old_path} -> {new_path}");
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+
+const TAIL_CHUNK_BYTES: u64 = 8 * 1024;
+
+/// Returns the last `lines` lines of `path`, oldest first. Reads backwards in
+/// chunks so a large log costs roughly the size of the tail, not the file.
+pub(crate) fn read_tail_lines(path: &Path, lines: usize) -> io::Result<Vec<String>> {
+    if lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut file = File::open(path)?;
+    let mut position = file.metadata()?.len();
+    let mut tail: Vec<u8> = Vec::new();
+
+    // One extra newline: the file normally ends with one, which starts no line.
+    while position > 0 && tail.iter().filter(|byte| **byte == b'\n').count() <= lines {
+        let chunk_len = TAIL_CHUNK_BYTES.min(position);
+        position -= chunk_len;
+        file.seek(SeekFrom::Start(position))?;
+
+        let mut chunk = vec![0_u8; chunk_len as usize];
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+    }
+
+    let text = String::from_utf8_lossy(&tail);
+    let mut result: Vec<String> = text.lines().rev().take(lines).map(str::to_string).collect();
+    result.reverse();
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_log(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "live2d-desktop-pet-{name}-{}.log",
+            std::process::id()
+        ));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn returns_the_last_lines_oldest_first() {
+        let path = temp_log("tail-short", "one\ntwo\nthree\n");
+
+        assert_eq!(read_tail_lines(&path, 2).unwrap(), vec!["two", "three"]);
+        assert_eq!(
+            read_tail_lines(&path, 10).unwrap(),
+            vec!["one", "two", "three"]
+        );
+        assert!(read_tail_lines(&path, 0).unwrap().is_empty());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn keeps_a_last_line_without_a_newline() {
+        let path = temp_log("tail-unterminated", "one\ntwo\npartial");
+
+        assert_eq!(read_tail_lines(&path, 2).unwrap(), vec!["two", "partial"]);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn lines_spanning_chunk_boundaries_stay_whole() {
+        let contents: String = (0..2_000)
+            .map(|index| format!("line {index:05}\n"))
+            .collect();
+        assert!(contents.len() as u64 > 2 * TAIL_CHUNK_BYTES);
+        let path = temp_log("tail-chunks", &contents);
+
+        let tail = read_tail_lines(&path, 1_500).unwrap();
+        assert_eq!(tail.len(), 1_500);
+        assert_eq!(tail.first().unwrap(), "line 00500");
+        assert_eq!(tail.last().unwrap(), "line 01999");
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn empty_file_has_no_lines() {
+        let path = temp_log("tail-empty", "");
+
+        assert!(read_tail_lines(&path, 5).unwrap().is_empty());
+
+        let _ = fs::remove_file(&path);
+    }
+}
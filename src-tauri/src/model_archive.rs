@@ -0,0 +1,114 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+/// Separates the archive path from the entry path in virtual model paths,
+/// e.g. `characters.zip!hiyori/hiyori.model3.json`.
+pub const ARCHIVE_PATH_SEPARATOR: char = '!';
+
+// Archives beyond these bounds are refused instead of walked.
+const MAX_ARCHIVE_ENTRIES: usize = 10_000;
+const MAX_EXTRACTED_BYTES: u64 = 1024 * 1024 * 1024;
+
+fn open_archive(zip_path: &Path) -> Result<ZipArchive<File>, String> {
+    let file = File::open(zip_path)
+        .map_err(|error| format!("failed to open {}: {error}", zip_path.display()))?;
+    let archive = ZipArchive::new(file)
+        .map_err(|error| format!("failed to read archive {}: {error}", zip_path.display()))?;
+
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        return Err(format!(
+            "archive {} has {} entries (limit {MAX_ARCHIVE_ENTRIES})",
+            zip_path.display(),
+            archive.len()
+        ));
+    }
+    Ok(archive)
+}
+
+pub(crate) fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+}
+
+/// Lists `.model3.json` entries inside `zip_path` as virtual paths.
+pub(crate) fn find_model3_in_archive(zip_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let archive = open_archive(zip_path)?;
+    let archive_path = zip_path.to_string_lossy();
+
+    Ok(archive
+        .file_names()
+        .filter(|name| name.ends_with(".model3.json"))
+        .map(|name| PathBuf::from(format!("{archive_path}{ARCHIVE_PATH_SEPARATOR}{name}")))
+        .collect())
+}
+
+/// Extracts `zip_path` into `dest` and returns the first `.model3.json` written.
+#[tauri::command]
+pub fn extract_model(zip_path: String, dest: String) -> Result<String, String> {
+    let zip_path = PathBuf::from(zip_path);
+    let dest = PathBuf::from(dest);
+    let mut archive = open_archive(&zip_path)?;
+
+    fs::create_dir_all(&dest)
+        .map_err(|error| format!("failed to create {}: {error}", dest.display()))?;
+
+    let mut extracted_bytes = 0_u64;
+    let mut first_model: Option<PathBuf> = None;
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| format!("failed to read archive entry {index}: {error}"))?;
+
+        // Reject entries that would escape `dest` (absolute paths, `..`).
+        let Some(relative) = entry.enclosed_name() else {
+            tracing::warn!("skipping unsafe archive entry {}", entry.name());
+            continue;
+        };
+        let target = dest.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)
+                .map_err(|error| format!("failed to create {}: {error}", target.display()))?;
+            continue;
+        }
+
+        // Declared sizes can lie, so the cap is enforced on the bytes written.
+        let too_large = || {
+            format!(
+                "archive {} expands beyond {MAX_EXTRACTED_BYTES} bytes; extraction aborted",
+                zip_path.display()
+            )
+        };
+        if extracted_bytes.saturating_add(entry.size()) > MAX_EXTRACTED_BYTES {
+            return Err(too_large());
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|error| format!("failed to create {}: {error}", parent.display()))?;
+        }
+        let mut output = File::create(&target)
+            .map_err(|error| format!("failed to create {}: {error}", target.display()))?;
+        let remaining = MAX_EXTRACTED_BYTES - extracted_bytes;
+        let written = io::copy(&mut (&mut entry).take(remaining + 1), &mut output)
+            .map_err(|error| format!("failed to extract {}: {error}", target.display()))?;
+        extracted_bytes += written;
+        if extracted_bytes > MAX_EXTRACTED_BYTES {
+            drop(output);
+            let _ = fs::remove_file(&target);
+            return Err(too_large());
+        }
+
+        if first_model.is_none() && entry.name().ends_with(".model3.json") {
+            first_model = Some(target);
+        }
+    }
+
+    first_model
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| "Archive extracted, but it contains no .model3.json file.".to_string())
+}
@@ -0,0 +1,208 @@
+use crate::library::{load_library_roots, stored_fingerprint};
+use crate::model_scan::{
+    scan_model3_files, validate_scan_root, FailedRoot, ScanOptions, ScanRegistration,
+    SEARCH_MAX_DEPTH,
+};
+use crate::model_validate::{read_model3_json, resolve_reference};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+// The moc3 header carries the format magic and version; hashing only it keeps
+// fingerprinting cheap for multi-megabyte mocs.
+const MOC3_HEADER_BYTES: u64 = 64;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a. Chosen because its output never changes between Rust
+/// releases, unlike `DefaultHasher`; it is not collision resistant.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+/// A stable id for a model built from its `.model3.json` bytes and the header of
+/// its `.moc3`, so a copy in another folder gets the same id. This identifies a
+/// model for relinking; it is not a security check.
+pub(crate) fn fingerprint_model(model_path: &Path) -> Result<String, String> {
+    // Parsing first applies the model3.json size limit before the raw read.
+    let model = read_model3_json(model_path)?;
+    let content = fs::read(model_path)
+        .map_err(|error| format!("failed to read {}: {error}", model_path.display()))?;
+
+    let mut hasher = Fnv1a::new();
+    hasher.write(&content);
+
+    if let Some(moc) = model.file_references.moc.as_deref() {
+        let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+        let moc_path = resolve_reference(model_dir, moc);
+        let mut header = Vec::new();
+        File::open(&moc_path)
+            .and_then(|file| file.take(MOC3_HEADER_BYTES).read_to_end(&mut header))
+            .map_err(|error| format!("failed to read {}: {error}", moc_path.display()))?;
+        hasher.write(&header);
+    }
+
+    Ok(format!("{:016x}", hasher.0))
+}
+
+#[tauri::command]
+pub fn model_fingerprint(path: String) -> Result<String, String> {
+    fingerprint_model(Path::new(path.trim()))
+}
+
+/// Looks for a model whose fingerprint matches the one remembered for `path`
+/// by scanning the library roots. Returns the new path, or `None` when the
+/// model was never fingerprinted or no copy is found.
+#[tauri::command(async)]
+pub fn locate_moved_model(app: AppHandle, path: String) -> Result<Option<String>, String> {
+    let Some(fingerprint) = stored_fingerprint(&app, path.trim()) else {
+        return Ok(None);
+    };
+
+    let scan = ScanRegistration::register();
+    let options = ScanOptions {
+        skip_hidden: true,
+        max_depth: Some(SEARCH_MAX_DEPTH),
+        cancel: scan.cancel_flag(),
+        ..ScanOptions::default()
+    };
+    for root in load_library_roots(&app) {
+        let root = match validate_scan_root(&root) {
+            Ok(root) => root,
+            Err(error) => {
+                tracing::warn!("skipping library root {root} while relocating: {error}");
+                continue;
+            }
+        };
+        let found = scan_model3_files(&root, &options)
+            .models
+            .into_iter()
+            .find(|candidate| fingerprint_model(candidate).ok().as_ref() == Some(&fingerprint));
+        if let Some(found) = found {
+            return Ok(Some(found.to_string_lossy().to_string()));
+        }
+    }
+    Ok(None)
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateModels {
+    /// Paths sharing a fingerprint, sorted; only groups of two or more.
+    pub groups: Vec<Vec<String>>,
+    /// Model files and directories that could not be read.
+    pub skipped_count: usize,
+    pub failed_roots: Vec<FailedRoot>,
+}
+
+/// Fingerprints every model under `root`. The count is of models that could
+/// not be fingerprinted plus directories that could not be listed.
+fn fingerprint_root(
+    root: &str,
+    options: &ScanOptions,
+) -> Result<(Vec<(PathBuf, String)>, usize), String> {
+    let root = validate_scan_root(root)?;
+    let outcome = scan_model3_files(&root, options);
+    let mut skipped = outcome.skipped_dir_count;
+    let mut fingerprints = Vec::with_capacity(outcome.models.len());
+    for model in outcome.models {
+        match fingerprint_model(&model) {
+            Ok(fingerprint) => fingerprints.push((model, fingerprint)),
+            Err(error) => {
+                tracing::debug!("skipping unreadable model {}: {error}", model.display());
+                skipped += 1;
+            }
+        }
+    }
+    Ok((fingerprints, skipped))
+}
+
+/// Groups the models under `roots` by fingerprint to find copies of the same
+/// model in different folders. Roots are indexed concurrently; overlapping
+/// roots do not report a model as its own duplicate. Without `roots` (or with
+/// an empty list) the saved library roots are searched.
+#[tauri::command(async)]
+pub fn find_duplicate_models(app: AppHandle, roots: Option<Vec<String>>) -> DuplicateModels {
+    let roots = roots
+        .filter(|roots| !roots.is_empty())
+        .unwrap_or_else(|| load_library_roots(&app));
+    let scan = ScanRegistration::register();
+    let options = ScanOptions {
+        skip_hidden: true,
+        max_depth: Some(SEARCH_MAX_DEPTH),
+        cancel: scan.cancel_flag(),
+        ..ScanOptions::default()
+    };
+
+    let results: Vec<(String, Result<_, String>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = roots
+            .iter()
+            .map(|root| {
+                let options = &options;
+                scope.spawn(move || fingerprint_root(root, options))
+            })
+            .collect();
+
+        roots
+            .iter()
+            .cloned()
+            .zip(handles)
+            .map(|(root, handle)| {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err("fingerprint thread panicked".to_string()));
+                (root, result)
+            })
+            .collect()
+    });
+
+    let mut fingerprints: BTreeMap<PathBuf, String> = BTreeMap::new();
+    let mut skipped_count = 0;
+    let mut failed_roots = Vec::new();
+    for (root, result) in results {
+        match result {
+            Ok((models, skipped)) => {
+                fingerprints.extend(models);
+                skipped_count += skipped;
+            }
+            Err(error) => {
+                tracing::warn!("duplicate search failed for root {root}: {error}");
+                failed_roots.push(FailedRoot { root, error });
+            }
+        }
+    }
+
+    let mut by_fingerprint: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (path, fingerprint) in fingerprints {
+        by_fingerprint
+            .entry(fingerprint)
+            .or_default()
+            .push(path.to_string_lossy().to_string());
+    }
+    let mut groups: Vec<Vec<String>> = by_fingerprint
+        .into_values()
+        .filter(|paths| paths.len() > 1)
+        .collect();
+    groups.sort();
+
+    DuplicateModels {
+        groups,
+        skipped_count,
+        failed_roots,
+    }
+}
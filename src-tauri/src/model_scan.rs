@@ -1,9 +1,155 @@
+use crate::library::{load_library_roots, ModelEntry};
+use crate::model_archive::{find_model3_in_archive, is_archive};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
 
-#[tauri::command]
-pub fn find_model3_json(directory: String) -> Result<String, String> {
-    let root = PathBuf::from(&directory);
+const MAX_REPORTED_SKIPPED_DIRS: usize = 50;
+pub(crate) const SEARCH_MAX_DEPTH: usize = 8;
+
+/// Canonical root prefixes scans may start from. Empty means unrestricted.
+static SCAN_ALLOWLIST: Lazy<RwLock<Vec<PathBuf>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Scans in flight -> their cancel flag. Each entry is removed when its
+/// `ScanRegistration` drops, whether the scan finished, failed or was cancelled.
+static ACTIVE_SCANS: Lazy<Mutex<HashMap<u64, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_SCAN_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFile {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelScanResult {
+    pub models: Vec<ModelFile>,
+    pub skipped_dir_count: usize,
+    pub skipped_dirs: Vec<String>,
+}
+
+#[derive(Default)]
+pub(crate) struct ScanOptions {
+    pub first_only: bool,
+    /// Directory-name globs (`build`, `cache*`) or, when containing `/`, globs
+    /// matched against the directory path relative to the scan root.
+    pub ignore: Vec<String>,
+    /// Also look inside `.zip` files and report virtual `archive.zip!entry` paths.
+    pub include_archives: bool,
+    /// Skip dot-directories such as `.git` or `.cache`.
+    pub skip_hidden: bool,
+    /// Maximum directory depth below the root; `None` walks everything.
+    pub max_depth: Option<usize>,
+    /// When set, the walk stops early and returns what it found so far.
+    pub cancel: Option<Arc<AtomicBool>>,
+}
+
+fn active_scans() -> std::sync::MutexGuard<'static, HashMap<u64, Arc<AtomicBool>>> {
+    ACTIVE_SCANS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Keeps a scan listed in `list_active_scans` for as long as it is alive.
+pub(crate) struct ScanRegistration {
+    id: u64,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ScanRegistration {
+    pub(crate) fn register() -> Self {
+        let id = NEXT_SCAN_ID.fetch_add(1, Ordering::Relaxed);
+        let cancel = Arc::new(AtomicBool::new(false));
+        active_scans().insert(id, Arc::clone(&cancel));
+        Self { id, cancel }
+    }
+
+    pub(crate) fn cancel_flag(&self) -> Option<Arc<AtomicBool>> {
+        Some(Arc::clone(&self.cancel))
+    }
+}
+
+impl Drop for ScanRegistration {
+    fn drop(&mut self) {
+        active_scans().remove(&self.id);
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct ScanOutcome {
+    pub models: Vec<PathBuf>,
+    pub skipped_dir_count: usize,
+    pub skipped_dirs: Vec<PathBuf>,
+}
+
+impl ScanOutcome {
+    fn record_skipped_dir(&mut self, dir: &Path, error: &std::io::Error) {
+        tracing::debug!("skipping unreadable directory {}: {error}", dir.display());
+        self.skipped_dir_count += 1;
+        if self.skipped_dirs.len() < MAX_REPORTED_SKIPPED_DIRS {
+            self.skipped_dirs.push(dir.to_path_buf());
+        }
+    }
+}
+
+/// Restricts scans to the user's home, documents and downloads directories
+/// plus the saved library roots, which the user added explicitly and which
+/// may live elsewhere (e.g. another drive). Re-run whenever the roots change.
+pub(crate) fn init_scan_allowlist(app: &AppHandle) {
+    let resolver = app.path();
+    let mut roots: Vec<PathBuf> = [
+        resolver.home_dir(),
+        resolver.document_dir(),
+        resolver.download_dir(),
+    ]
+    .into_iter()
+    .filter_map(Result::ok)
+    .chain(load_library_roots(app).into_iter().map(PathBuf::from))
+    .filter_map(|path| path.canonicalize().ok())
+    .collect();
+    roots.dedup();
+
+    tracing::info!("model scan allowlist: {roots:?}");
+    if let Ok(mut allowlist) = SCAN_ALLOWLIST.write() {
+        *allowlist = roots;
+    }
+}
+
+fn is_allowed_scan_root(root: &Path) -> bool {
+    let Ok(allowlist) = SCAN_ALLOWLIST.read() else {
+        return false;
+    };
+    allowlist.is_empty() || allowlist.iter().any(|allowed| root.starts_with(allowed))
+}
+
+/// Paths the frontend loads without touching the filesystem (see
+/// `resolveModelSource` in `PetStage.tsx`) are treated as always present.
+pub(crate) fn model_path_exists(path: &str) -> bool {
+    let path = path.trim();
+    if ["/models/", "http://", "https://", "asset://"]
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return true;
+    }
+    Path::new(path).is_file()
+}
+
+/// Canonical form of an existing directory, without the allowlist check.
+pub(crate) fn resolve_scan_dir(directory: &str) -> Result<PathBuf, String> {
+    let root = PathBuf::from(directory);
     if !root.exists() {
         return Err("Directory does not exist.".to_string());
     }
@@ -11,24 +157,342 @@ pub fn find_model3_json(directory: String) -> Result<String, String> {
         return Err("Selected path is not a directory.".to_string());
     }
 
-    find_first_model3_file(&root)
+    root.canonicalize()
+        .map_err(|error| format!("Failed to resolve directory: {error}"))
+}
+
+pub(crate) fn validate_scan_root(directory: &str) -> Result<PathBuf, String> {
+    let root = resolve_scan_dir(directory)?;
+    if !is_allowed_scan_root(&root) {
+        tracing::warn!("refusing to scan outside allowed roots: {}", root.display());
+        return Err("Directory is outside the allowed scan locations.".to_string());
+    }
+    Ok(root)
+}
+
+fn no_model_found_message(outcome: &ScanOutcome) -> String {
+    match outcome.skipped_dir_count {
+        0 => "No .model3.json file found under selected directory.".to_string(),
+        count => format!(
+            "No .model3.json file found under selected directory ({count} folder(s) could not be read; check file access permissions)."
+        ),
+    }
+}
+
+#[tauri::command]
+pub fn find_model3_json(directory: String, ignore: Option<Vec<String>>) -> Result<String, String> {
+    let root = validate_scan_root(&directory)?;
+    let outcome = scan_model3_files(
+        &root,
+        &ScanOptions {
+            first_only: true,
+            ignore: ignore.unwrap_or_default(),
+            ..ScanOptions::default()
+        },
+    );
+
+    outcome
+        .models
+        .first()
         .map(|path| path.to_string_lossy().to_string())
-        .ok_or_else(|| "No .model3.json file found under selected directory.".to_string())
+        .ok_or_else(|| no_model_found_message(&outcome))
 }
 
-fn find_first_model3_file(root: &Path) -> Option<PathBuf> {
-    let mut stack = vec![root.to_path_buf()];
+pub(crate) fn model_file_info(path: &Path) -> ModelFile {
+    let metadata = fs::metadata(path)
+        .map_err(|error| {
+            tracing::debug!("failed to stat {}: {error}", path.display());
+        })
+        .ok();
 
-    while let Some(dir) = stack.pop() {
+    ModelFile {
+        path: path.to_string_lossy().to_string(),
+        size_bytes: metadata.as_ref().map(|metadata| metadata.len()),
+        modified_ms: metadata
+            .and_then(|metadata| metadata.modified().ok())
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as u64),
+    }
+}
+
+#[tauri::command]
+pub fn find_all_model3_json(
+    directory: String,
+    ignore: Option<Vec<String>>,
+    filter: Option<String>,
+    include_archives: Option<bool>,
+) -> Result<ModelScanResult, String> {
+    let root = validate_scan_root(&directory)?;
+    let scan = ScanRegistration::register();
+    let outcome = scan_model3_files(
+        &root,
+        &ScanOptions {
+            first_only: false,
+            ignore: ignore.unwrap_or_default(),
+            include_archives: include_archives.unwrap_or(false),
+            cancel: scan.cancel_flag(),
+            ..ScanOptions::default()
+        },
+    );
+
+    let models = rank_models(&outcome.models, filter.as_deref());
+
+    Ok(ModelScanResult {
+        models: models
+            .into_iter()
+            .map(|path| model_file_info(path))
+            .collect(),
+        skipped_dir_count: outcome.skipped_dir_count,
+        skipped_dirs: outcome
+            .skipped_dirs
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect(),
+    })
+}
+
+/// Applies the fuzzy `filter` (if any), best match first.
+fn rank_models<'a>(models: &'a [PathBuf], filter: Option<&str>) -> Vec<&'a PathBuf> {
+    let query = filter.map(str::trim).unwrap_or_default();
+    if query.is_empty() {
+        return models.iter().collect();
+    }
+
+    let mut ranked: Vec<(i64, &PathBuf)> = models
+        .iter()
+        .filter_map(|path| model_match_score(path, query).map(|score| (score, path)))
+        .collect();
+    ranked.sort_by(|left, right| right.0.cmp(&left.0).then_with(|| left.1.cmp(right.1)));
+    ranked.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Case-insensitive subsequence match. Consecutive hits and hits at word
+/// boundaries score higher; a late first hit costs a little.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0_i64;
+    let mut position = 0_usize;
+    let mut previous_match: Option<usize> = None;
+    let mut first_match: Option<usize> = None;
+
+    for wanted in query.to_lowercase().chars() {
+        let offset = candidate[position..].iter().position(|&c| c == wanted)?;
+        let index = position + offset;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || matches!(candidate[index - 1], '_' | '-' | ' ' | '.') {
+            score += 3;
+        }
+
+        first_match.get_or_insert(index);
+        previous_match = Some(index);
+        position = index + 1;
+    }
+
+    Some(score - first_match.unwrap_or(0).min(10) as i64)
+}
+
+fn model_match_score(path: &Path, query: &str) -> Option<i64> {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let folder_name = path
+        .parent()
+        .and_then(|parent| parent.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    [file_name, folder_name]
+        .iter()
+        .filter_map(|candidate| fuzzy_score(query, candidate))
+        .max()
+}
+
+pub(crate) fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], text) || (!text.is_empty() && glob_match(pattern, &text[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &text[1..]),
+        (Some(expected), Some(actual)) if expected.eq_ignore_ascii_case(actual) => {
+            glob_match(&pattern[1..], &text[1..])
+        }
+        _ => false,
+    }
+}
+
+fn is_ignored_dir(root: &Path, dir: &Path, ignore: &[String]) -> bool {
+    if ignore.is_empty() {
+        return false;
+    }
+
+    let name = dir
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let relative = dir
+        .strip_prefix(root)
+        .map(|relative| {
+            relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default();
+
+    ignore.iter().any(|pattern| {
+        let pattern = pattern.trim().trim_matches('/');
+        if pattern.is_empty() {
+            return false;
+        }
+        if pattern.contains('/') {
+            glob_match(pattern.as_bytes(), relative.as_bytes())
+        } else {
+            glob_match(pattern.as_bytes(), name.as_bytes())
+        }
+    })
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedRoot {
+    pub root: String,
+    pub error: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSearchResult {
+    pub models: Vec<ModelEntry>,
+    pub failed_roots: Vec<FailedRoot>,
+}
+
+/// Searches several library roots concurrently. A root that cannot be scanned
+/// is reported in `failedRoots` without discarding the others' results.
+/// Without explicit `roots` the saved library roots are searched.
+#[tauri::command]
+pub fn search_models(
+    app: AppHandle,
+    roots: Option<Vec<String>>,
+    filter: Option<String>,
+) -> ModelSearchResult {
+    let roots = roots.unwrap_or_else(|| load_library_roots(&app));
+    let scan = ScanRegistration::register();
+    let options = ScanOptions {
+        skip_hidden: true,
+        max_depth: Some(SEARCH_MAX_DEPTH),
+        cancel: scan.cancel_flag(),
+        ..ScanOptions::default()
+    };
+
+    let results: Vec<(String, Result<ScanOutcome, String>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = roots
+            .iter()
+            .map(|root| {
+                let options = &options;
+                scope.spawn(move || {
+                    validate_scan_root(root).map(|path| scan_model3_files(&path, options))
+                })
+            })
+            .collect();
+
+        roots
+            .iter()
+            .cloned()
+            .zip(handles)
+            .map(|(root, handle)| {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err("scan thread panicked".to_string()));
+                (root, result)
+            })
+            .collect()
+    });
+
+    let mut models: Vec<PathBuf> = Vec::new();
+    let mut failed_roots = Vec::new();
+    for (root, result) in results {
+        match result {
+            Ok(outcome) => models.extend(outcome.models),
+            Err(error) => {
+                tracing::warn!("model search failed for root {root}: {error}");
+                failed_roots.push(FailedRoot { root, error });
+            }
+        }
+    }
+    models.sort();
+    models.dedup();
+
+    ModelSearchResult {
+        models: rank_models(&models, filter.as_deref())
+            .into_iter()
+            .map(|path| ModelEntry::from_path(&path.to_string_lossy()))
+            .collect(),
+        failed_roots,
+    }
+}
+
+pub(crate) fn scan_model3_files(root: &Path, options: &ScanOptions) -> ScanOutcome {
+    let mut outcome = ScanOutcome::default();
+    let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut visited_dirs: HashSet<PathBuf> = HashSet::new();
+    let mut seen_models: HashSet<PathBuf> = HashSet::new();
+    let mut stack = vec![(root.to_path_buf(), 0_usize)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        if let Some(cancel) = options.cancel.as_deref() {
+            if cancel.load(Ordering::Relaxed) {
+                tracing::debug!("scan of {} cancelled", root.display());
+                break;
+            }
+        }
+        // Followed links can reach a folder twice or loop back to an ancestor.
+        if !visited_dirs.insert(dir.canonicalize().unwrap_or_else(|_| dir.clone())) {
+            continue;
+        }
         let entries = match fs::read_dir(&dir) {
             Ok(entries) => entries,
-            Err(_) => continue,
+            Err(error) => {
+                outcome.record_skipped_dir(&dir, &error);
+                continue;
+            }
         };
 
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.is_dir() {
-                stack.push(path);
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // Links are only followed while their target stays inside the scan
+            // root; one leading out of it could take the scan past the allowlist.
+            let is_dir = if file_type.is_symlink() {
+                match path.canonicalize() {
+                    Ok(target) if target.starts_with(&canonical_root) => target.is_dir(),
+                    _ => {
+                        tracing::debug!("skipping symlink out of the root {}", path.display());
+                        continue;
+                    }
+                }
+            } else {
+                file_type.is_dir()
+            };
+            if is_dir {
+                let hidden =
+                    options.skip_hidden && entry.file_name().to_string_lossy().starts_with('.');
+                let too_deep = options
+                    .max_depth
+                    .is_some_and(|max_depth| depth >= max_depth);
+                if hidden || too_deep || is_ignored_dir(root, &path, &options.ignore) {
+                    tracing::debug!("skipping directory {}", path.display());
+                } else {
+                    stack.push((path, depth + 1));
+                }
                 continue;
             }
 
@@ -38,13 +502,182 @@ fn find_first_model3_file(root: &Path) -> Option<PathBuf> {
             };
 
             if name.ends_with(".model3.json") {
-                if let Ok(canonical) = path.canonicalize() {
-                    return Some(canonical);
+                let model = path.canonicalize().unwrap_or(path);
+                if seen_models.insert(model.clone()) {
+                    outcome.models.push(model);
+                }
+                if options.first_only {
+                    return outcome;
+                }
+                continue;
+            }
+
+            if options.include_archives && is_archive(&path) {
+                match find_model3_in_archive(&path) {
+                    Ok(models) => outcome.models.extend(models),
+                    Err(error) => tracing::debug!("skipping archive: {error}"),
                 }
-                return Some(path);
             }
         }
     }
 
-    None
+    outcome
+}
+
+/// Ids of the scans currently running, oldest first.
+#[tauri::command]
+pub fn list_active_scans() -> Vec<u64> {
+    let mut ids: Vec<u64> = active_scans().keys().copied().collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Asks every running scan to stop; each returns what it found so far and
+/// then drops out of `list_active_scans`. Returns how many were signalled.
+#[tauri::command]
+pub fn cancel_all_scans() -> usize {
+    let scans = active_scans();
+    for cancel in scans.values() {
+        cancel.store(true, Ordering::Relaxed);
+    }
+    scans.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("live2d-desktop-pet-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    fn touch(path: PathBuf) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "{}").unwrap();
+    }
+
+    #[test]
+    fn glob_match_supports_wildcards() {
+        assert!(glob_match(b"build", b"build"));
+        assert!(glob_match(b"cache*", b"cache-v2"));
+        assert!(glob_match(b"*.tmp", b"work.TMP"));
+        assert!(glob_match(b"v?", b"v1"));
+        assert!(!glob_match(b"build", b"builds"));
+        assert!(!glob_match(b"v?", b"v10"));
+    }
+
+    #[test]
+    fn scan_skips_ignored_directories() {
+        let root = temp_tree("scan-ignore");
+        touch(root.join("build/a/skip.model3.json"));
+        touch(root.join("cache-old/skip.model3.json"));
+        touch(root.join("assets/generated/skip.model3.json"));
+        touch(root.join("assets/hiyori/hiyori.model3.json"));
+
+        let outcome = scan_model3_files(
+            &root,
+            &ScanOptions {
+                first_only: false,
+                ignore: vec![
+                    "build".to_string(),
+                    "cache*".to_string(),
+                    "assets/generated".to_string(),
+                ],
+                ..ScanOptions::default()
+            },
+        );
+        let names: Vec<_> = outcome
+            .models
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["hiyori.model3.json".to_string()]);
+
+        let unfiltered = scan_model3_files(&root, &ScanOptions::default());
+        assert_eq!(unfiltered.models.len(), 4);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_does_not_follow_symlinks_out_of_the_root() {
+        let root = temp_tree("scan-symlink");
+        let outside = temp_tree("scan-symlink-outside");
+        touch(outside.join("escaped/escaped.model3.json"));
+        touch(root.join("own/own.model3.json"));
+        std::os::unix::fs::symlink(outside.join("escaped"), root.join("link")).unwrap();
+
+        let outcome = scan_model3_files(&root, &ScanOptions::default());
+        assert_eq!(outcome.models.len(), 1);
+        assert!(outcome.models[0].ends_with("own/own.model3.json"));
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_follows_symlinks_inside_the_root() {
+        let root = temp_tree("scan-symlink-inside");
+        touch(root.join("store/linked/linked.model3.json"));
+        touch(root.join("store/file/real.model3.json"));
+        std::os::unix::fs::symlink(root.join("store/linked"), root.join("models")).unwrap();
+        std::os::unix::fs::symlink(
+            root.join("store/file/real.model3.json"),
+            root.join("alias.model3.json"),
+        )
+        .unwrap();
+        std::os::unix::fs::symlink(&root, root.join("store/loop")).unwrap();
+
+        let outcome = scan_model3_files(&root, &ScanOptions::default());
+        let mut names: Vec<_> = outcome
+            .models
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["linked.model3.json", "real.model3.json"]);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn finished_and_cancelled_scans_leave_the_registry() {
+        let root = temp_tree("scan-registry");
+        touch(root.join("a/a.model3.json"));
+        touch(root.join("b/b.model3.json"));
+
+        let found: Vec<usize> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|index| {
+                    let root = &root;
+                    scope.spawn(move || {
+                        let scan = ScanRegistration::register();
+                        if index % 2 == 1 {
+                            scan.cancel.store(true, Ordering::Relaxed);
+                        }
+                        let options = ScanOptions {
+                            cancel: scan.cancel_flag(),
+                            ..ScanOptions::default()
+                        };
+                        scan_model3_files(root, &options).models.len()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        assert_eq!(found, vec![2, 0, 2, 0]);
+        assert!(list_active_scans().is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
 }
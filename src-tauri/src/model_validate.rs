@@ -0,0 +1,650 @@
+use crate::diagnostics::SharedDiagnosticsState;
+use crate::model_scan::find_model3_json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Instant;
+use tauri::State;
+
+const DEFAULT_TEXTURE_WARNING_THRESHOLD_PX: u32 = 4096;
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const MOC3_MAGIC: &[u8; 4] = b"MOC3";
+// The section offset table starts right after the 64-byte header; its second
+// entry points at the canvas info (pixels per unit, origin x/y, width, height).
+const MOC3_SECTION_TABLE_OFFSET: usize = 64;
+// Canvas sizes beyond this are a misread, not a model.
+const MAX_CANVAS_SIDE_PX: f32 = 16_384.0;
+
+// Real model3.json files are a few KB; anything past this is corrupt or hostile.
+const DEFAULT_MODEL3_MAX_BYTES: u64 = 4 * 1024 * 1024;
+
+static MODEL3_MAX_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_MODEL3_MAX_BYTES);
+static TEXTURE_WARNING_THRESHOLD_PX: AtomicU32 =
+    AtomicU32::new(DEFAULT_TEXTURE_WARNING_THRESHOLD_PX);
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Model3Json {
+    #[serde(default)]
+    pub file_references: Model3FileReferences,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Model3FileReferences {
+    pub moc: Option<String>,
+    #[serde(default)]
+    pub textures: Vec<String>,
+    pub physics: Option<String>,
+    pub pose: Option<String>,
+    pub motions: Option<HashMap<String, Vec<Model3MotionRef>>>,
+    pub expressions: Option<Vec<Model3ExpressionRef>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Model3MotionRef {
+    pub file: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Model3ExpressionRef {
+    /// Optional in the format; unnamed expressions are listed by file stem.
+    #[serde(default)]
+    pub name: String,
+    pub file: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingReference {
+    pub kind: String,
+    pub declared_path: String,
+    pub resolved_path: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatedModel {
+    pub model_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moc_path: Option<String>,
+    pub texture_paths: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub physics_path: Option<String>,
+    pub valid: bool,
+    /// Set when any texture side exceeds the configured warning threshold.
+    pub heavy_textures: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub largest_texture_dimension: Option<u32>,
+    pub missing: Vec<MissingReference>,
+    pub problems: Vec<String>,
+    pub summary: String,
+}
+
+/// Rust-side cost of loading a model, per phase, in milliseconds. GPU upload
+/// in the renderer is not included.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseBenchmark {
+    pub model_path: String,
+    pub read_ms: f64,
+    pub parse_ms: f64,
+    pub resolve_ms: f64,
+    pub stat_ms: f64,
+    pub total_ms: f64,
+    pub model3_bytes: u64,
+    /// Distinct referenced files, and how many of them were not found.
+    pub referenced_files: usize,
+    pub missing_files: usize,
+    /// Sum of the referenced files that exist.
+    pub referenced_bytes: u64,
+}
+
+/// The size a model is authored at, in pixels. `source` is `"moc3"` when read
+/// from the moc's canvas info, or `"texture"` for the first texture's size.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CanvasSize {
+    pub width: f64,
+    pub height: f64,
+    pub source: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelExpression {
+    pub name: String,
+    pub file: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelAnimations {
+    /// Group name -> motion file paths; the position is the motion index.
+    pub motion_groups: HashMap<String, Vec<String>>,
+    pub expressions: Vec<ModelExpression>,
+}
+
+pub(crate) fn read_model3_json(model_path: &Path) -> Result<Model3Json, String> {
+    read_model3_json_with_limit(model_path, MODEL3_MAX_BYTES.load(Ordering::Relaxed))
+}
+
+/// Reads a `.model3.json` whole, refusing files over `max_bytes`.
+fn read_model3_bytes(model_path: &Path, max_bytes: u64) -> Result<Vec<u8>, String> {
+    let size = fs::metadata(model_path)
+        .map_err(|error| format!("failed to read {}: {error}", model_path.display()))?
+        .len();
+    if size > max_bytes {
+        return Err(format!(
+            "{} is {size} bytes, larger than the {max_bytes} byte limit for model3.json files.",
+            model_path.display()
+        ));
+    }
+
+    let file = File::open(model_path)
+        .map_err(|error| format!("failed to read {}: {error}", model_path.display()))?;
+    let mut bytes = Vec::with_capacity(size as usize);
+    // `take` keeps the guard honest if the file grows after the metadata check.
+    file.take(max_bytes)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("failed to read {}: {error}", model_path.display()))?;
+    Ok(bytes)
+}
+
+fn parse_model3_json(model_path: &Path, bytes: &[u8]) -> Result<Model3Json, String> {
+    serde_json::from_slice(bytes)
+        .map_err(|error| format!("failed to parse {}: {error}", model_path.display()))
+}
+
+fn read_model3_json_with_limit(model_path: &Path, max_bytes: u64) -> Result<Model3Json, String> {
+    parse_model3_json(model_path, &read_model3_bytes(model_path, max_bytes)?)
+}
+
+/// Resolves a `FileReferences` entry against the model directory. Declared paths
+/// come from authoring tools on any OS, so both `/` and `\\` are treated as separators.
+pub(crate) fn resolve_reference(model_dir: &Path, declared: &str) -> PathBuf {
+    let mut resolved = model_dir.to_path_buf();
+    for segment in declared.split(['/', '\\']) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                resolved.pop();
+            }
+            _ => resolved.push(segment),
+        }
+    }
+    resolved
+}
+
+struct ReferenceChecker<'a> {
+    model_dir: &'a Path,
+    missing: Vec<MissingReference>,
+    problems: Vec<String>,
+}
+
+impl ReferenceChecker<'_> {
+    fn check(&mut self, kind: &str, declared: &str) -> String {
+        let resolved = resolve_reference(self.model_dir, declared);
+        let resolved_path = resolved.to_string_lossy().to_string();
+        if !resolved.is_file() {
+            self.problems.push(format!(
+                "{kind} file not found: {declared} (looked for {resolved_path})"
+            ));
+            self.missing.push(MissingReference {
+                kind: kind.to_string(),
+                declared_path: declared.to_string(),
+                resolved_path: resolved_path.clone(),
+            });
+        }
+        resolved_path
+    }
+}
+
+/// Reads width/height from the PNG IHDR chunk without decoding the image.
+fn read_png_dimensions(path: &Path) -> Option<(u32, u32)> {
+    let mut header = [0_u8; 24];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if header[..8] != PNG_SIGNATURE || &header[12..16] != b"IHDR" {
+        return None;
+    }
+
+    let width = u32::from_be_bytes(header[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(header[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+fn decode_u32(raw: [u8; 4], big_endian: bool) -> u32 {
+    if big_endian {
+        u32::from_be_bytes(raw)
+    } else {
+        u32::from_le_bytes(raw)
+    }
+}
+
+/// Reads the canvas width/height from a `.moc3` without parsing the rest of it.
+fn read_moc3_canvas(path: &Path) -> Option<(f32, f32)> {
+    let mut file = File::open(path).ok()?;
+    let mut header = [0_u8; MOC3_SECTION_TABLE_OFFSET + 8];
+    file.read_exact(&mut header).ok()?;
+    if &header[..4] != MOC3_MAGIC {
+        return None;
+    }
+    let big_endian = header[5] != 0;
+
+    let entry = MOC3_SECTION_TABLE_OFFSET + 4;
+    let canvas_info = decode_u32(header[entry..entry + 4].try_into().ok()?, big_endian);
+    let mut info = [0_u8; 20];
+    file.seek(SeekFrom::Start(u64::from(canvas_info))).ok()?;
+    file.read_exact(&mut info).ok()?;
+
+    let width = f32::from_bits(decode_u32(info[12..16].try_into().ok()?, big_endian));
+    let height = f32::from_bits(decode_u32(info[16..20].try_into().ok()?, big_endian));
+    let plausible = |side: f32| side.is_finite() && (1.0..=MAX_CANVAS_SIDE_PX).contains(&side);
+    (plausible(width) && plausible(height)).then_some((width, height))
+}
+
+fn summarize(problems: &[String]) -> String {
+    match problems.len() {
+        0 => "Model looks complete.".to_string(),
+        1 => format!("1 problem found: {}", problems[0]),
+        count => format!("{count} problems found: {}", problems.join("; ")),
+    }
+}
+
+pub(crate) fn validate_model3(model_path: &Path) -> Result<ValidatedModel, String> {
+    let model = read_model3_json(model_path)?;
+    let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+    let references = model.file_references;
+    let mut checker = ReferenceChecker {
+        model_dir,
+        missing: Vec::new(),
+        problems: Vec::new(),
+    };
+
+    let moc_path = match references.moc.as_deref() {
+        Some(declared) => Some(checker.check("moc", declared)),
+        None => {
+            checker
+                .problems
+                .push("FileReferences.Moc is missing.".to_string());
+            None
+        }
+    };
+
+    if references.textures.is_empty() {
+        checker
+            .problems
+            .push("FileReferences.Textures is empty.".to_string());
+    }
+    let texture_paths: Vec<String> = references
+        .textures
+        .iter()
+        .map(|declared| checker.check("texture", declared))
+        .collect();
+    let largest_texture_dimension = texture_paths
+        .iter()
+        .filter_map(|path| read_png_dimensions(Path::new(path)))
+        .map(|(width, height)| width.max(height))
+        .max();
+    let heavy_textures = largest_texture_dimension
+        .is_some_and(|largest| largest > TEXTURE_WARNING_THRESHOLD_PX.load(Ordering::Relaxed));
+
+    let physics_path = references
+        .physics
+        .as_deref()
+        .map(|declared| checker.check("physics", declared));
+
+    if let Some(declared) = references.pose.as_deref() {
+        checker.check("pose", declared);
+    }
+
+    let mut motion_groups: Vec<_> = references.motions.iter().flatten().collect();
+    motion_groups.sort_by(|left, right| left.0.cmp(right.0));
+    for (_, motions) in motion_groups {
+        for motion in motions {
+            checker.check("motion", &motion.file);
+        }
+    }
+
+    for expression in references.expressions.iter().flatten() {
+        checker.check("expression", &expression.file);
+    }
+
+    let ReferenceChecker {
+        missing, problems, ..
+    } = checker;
+
+    Ok(ValidatedModel {
+        model_path: model_path.to_string_lossy().to_string(),
+        moc_path,
+        texture_paths,
+        physics_path,
+        valid: problems.is_empty(),
+        heavy_textures,
+        largest_texture_dimension,
+        summary: summarize(&problems),
+        missing,
+        problems,
+    })
+}
+
+#[tauri::command]
+pub fn set_model3_max_bytes(bytes: u64) -> Result<u64, String> {
+    if bytes == 0 {
+        return Err("model3.json size limit must be greater than 0".to_string());
+    }
+    MODEL3_MAX_BYTES.store(bytes, Ordering::Relaxed);
+    Ok(bytes)
+}
+
+#[tauri::command]
+pub fn set_texture_warning_threshold(px: u32) -> Result<u32, String> {
+    if px == 0 {
+        return Err("texture warning threshold must be greater than 0".to_string());
+    }
+    TEXTURE_WARNING_THRESHOLD_PX.store(px, Ordering::Relaxed);
+    Ok(px)
+}
+
+#[tauri::command]
+pub fn pick_and_validate_model(
+    directory: String,
+    ignore: Option<Vec<String>>,
+) -> Result<ValidatedModel, String> {
+    let model_path = PathBuf::from(find_model3_json(directory, ignore)?);
+    validate_model3(&model_path)
+}
+
+#[tauri::command]
+pub fn list_model_animations(path: String) -> Result<ModelAnimations, String> {
+    let model_path = PathBuf::from(&path);
+    let model = read_model3_json(&model_path)?;
+    let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+    let references = model.file_references;
+
+    if references.motions.is_none() && references.expressions.is_none() {
+        return Err(
+            "model3.json has no FileReferences.Motions or FileReferences.Expressions section."
+                .to_string(),
+        );
+    }
+
+    let motion_groups = references
+        .motions
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(group, motions)| {
+            let files = motions
+                .iter()
+                .map(|motion| {
+                    resolve_reference(model_dir, &motion.file)
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect();
+            (group, files)
+        })
+        .collect();
+
+    let expressions = references
+        .expressions
+        .unwrap_or_default()
+        .into_iter()
+        .map(|expression| {
+            let file = resolve_reference(model_dir, &expression.file);
+            let name = if expression.name.is_empty() {
+                file.file_stem()
+                    .map(|stem| stem.to_string_lossy().trim_end_matches(".exp3").to_string())
+                    .unwrap_or_default()
+            } else {
+                expression.name
+            };
+            ModelExpression {
+                file: file.to_string_lossy().to_string(),
+                name,
+            }
+        })
+        .collect();
+
+    Ok(ModelAnimations {
+        motion_groups,
+        expressions,
+    })
+}
+
+/// `find_model3_json` then `list_model_animations` in one call, for previewing a
+/// folder before adopting it. A model with no motions or expressions is an
+/// error, distinct from the scan's "no model found".
+#[tauri::command]
+pub fn list_animations_in_dir(directory: String) -> Result<ModelAnimations, String> {
+    let model_path = find_model3_json(directory, None)?;
+    let animations = list_model_animations(model_path.clone())?;
+    if animations.motion_groups.values().all(Vec::is_empty) && animations.expressions.is_empty() {
+        return Err(format!("{model_path} has no animations."));
+    }
+    Ok(animations)
+}
+
+/// Every path declared in `FileReferences`, in no particular order.
+fn declared_references(references: &Model3FileReferences) -> Vec<&String> {
+    let motions = references.motions.iter().flat_map(|groups| {
+        groups
+            .values()
+            .flat_map(|motions| motions.iter().map(|motion| &motion.file))
+    });
+    let expressions = references
+        .expressions
+        .iter()
+        .flat_map(|expressions| expressions.iter().map(|expression| &expression.file));
+    references
+        .moc
+        .iter()
+        .chain(references.textures.iter())
+        .chain(references.physics.iter())
+        .chain(references.pose.iter())
+        .chain(motions)
+        .chain(expressions)
+        .collect()
+}
+
+fn elapsed_ms(since: Instant) -> f64 {
+    since.elapsed().as_secs_f64() * 1000.0
+}
+
+/// Times reading and parsing the `.model3.json`, resolving its references and
+/// stating each referenced file, to tell slow disks and parsing apart from
+/// slow GPU uploads. Missing references are counted, not treated as errors.
+/// The result is kept in the diagnostics' `parseBenchmarks` history.
+#[tauri::command(async)]
+pub fn benchmark_model_parse(
+    diagnostics: State<'_, SharedDiagnosticsState>,
+    path: String,
+) -> Result<ParseBenchmark, String> {
+    let model_path = PathBuf::from(path.trim());
+    let started = Instant::now();
+
+    let bytes = read_model3_bytes(&model_path, MODEL3_MAX_BYTES.load(Ordering::Relaxed))?;
+    let read_ms = elapsed_ms(started);
+
+    let phase = Instant::now();
+    let model = parse_model3_json(&model_path, &bytes)?;
+    let parse_ms = elapsed_ms(phase);
+
+    let phase = Instant::now();
+    let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+    let files: BTreeSet<PathBuf> = declared_references(&model.file_references)
+        .into_iter()
+        .map(|declared| resolve_reference(model_dir, declared))
+        .collect();
+    let resolve_ms = elapsed_ms(phase);
+
+    let phase = Instant::now();
+    let mut missing_files = 0;
+    let mut referenced_bytes = 0;
+    for file in &files {
+        match fs::metadata(file) {
+            Ok(metadata) => referenced_bytes += metadata.len(),
+            Err(_) => missing_files += 1,
+        }
+    }
+    let stat_ms = elapsed_ms(phase);
+
+    let benchmark = ParseBenchmark {
+        model_path: model_path.to_string_lossy().to_string(),
+        read_ms,
+        parse_ms,
+        resolve_ms,
+        stat_ms,
+        total_ms: elapsed_ms(started),
+        model3_bytes: bytes.len() as u64,
+        referenced_files: files.len(),
+        missing_files,
+        referenced_bytes,
+    };
+    tracing::info!(
+        "parse benchmark for {}: read {:.2} ms, parse {:.2} ms, resolve {:.2} ms, stat {:.2} ms ({} files)",
+        benchmark.model_path,
+        benchmark.read_ms,
+        benchmark.parse_ms,
+        benchmark.resolve_ms,
+        benchmark.stat_ms,
+        benchmark.referenced_files
+    );
+    diagnostics.record_parse_benchmark(benchmark.clone());
+    Ok(benchmark)
+}
+
+pub(crate) fn model_canvas_size_internal(model_path: &Path) -> Result<CanvasSize, String> {
+    let references = read_model3_json(model_path)?.file_references;
+    let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let from_moc = references
+        .moc
+        .as_deref()
+        .and_then(|declared| read_moc3_canvas(&resolve_reference(model_dir, declared)))
+        .map(|(width, height)| (f64::from(width), f64::from(height), "moc3"));
+    let from_texture = || {
+        let declared = references.textures.first()?;
+        let (width, height) = read_png_dimensions(&resolve_reference(model_dir, declared))?;
+        (width > 0 && height > 0).then_some((f64::from(width), f64::from(height), "texture"))
+    };
+
+    let (width, height, source) = from_moc.or_else(from_texture).ok_or_else(|| {
+        format!(
+            "{} has no readable moc3 canvas or PNG texture to size it from.",
+            model_path.display()
+        )
+    })?;
+    Ok(CanvasSize {
+        width,
+        height,
+        source: source.to_string(),
+    })
+}
+
+/// The canvas size of a model from its `.moc3`, falling back to the first
+/// texture's dimensions. Errors when neither can be read.
+#[tauri::command]
+pub fn model_canvas_size(path: String) -> Result<CanvasSize, String> {
+    model_canvas_size_internal(Path::new(path.trim()))
+}
+
+/// Bytes on disk for the `.model3.json` plus every file it references (moc,
+/// textures, physics, pose, motions, expressions), each counted once. Missing
+/// references are logged and left out of the total instead of failing.
+#[tauri::command]
+pub fn model_disk_size(path: String) -> Result<u64, String> {
+    let model_path = PathBuf::from(path.trim());
+    let model_size = fs::metadata(&model_path)
+        .map_err(|error| format!("failed to read {}: {error}", model_path.display()))?
+        .len();
+    let references = read_model3_json(&model_path)?.file_references;
+    let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+
+    let files: BTreeSet<PathBuf> = declared_references(&references)
+        .into_iter()
+        .map(|declared| resolve_reference(model_dir, declared))
+        .collect();
+
+    let mut total = model_size;
+    let mut missing = Vec::new();
+    for file in &files {
+        match fs::metadata(file) {
+            Ok(metadata) => total += metadata.len(),
+            Err(_) => missing.push(file.display().to_string()),
+        }
+    }
+    if !missing.is_empty() {
+        tracing::warn!(
+            "{} references {} missing file(s), not counted: {}",
+            model_path.display(),
+            missing.len(),
+            missing.join(", ")
+        );
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_model3_json_is_rejected() {
+        let path = std::env::temp_dir().join(format!(
+            "live2d-desktop-pet-oversized-{}.model3.json",
+            std::process::id()
+        ));
+        let padding = " ".repeat(2048);
+        fs::write(&path, format!("{{\"FileReferences\": {{}}{padding}}}")).unwrap();
+
+        let error = read_model3_json_with_limit(&path, 1024).unwrap_err();
+        assert!(error.contains("byte limit"), "unexpected error: {error}");
+        assert!(read_model3_json_with_limit(&path, 4096).is_ok());
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn expressions_without_a_name_still_parse() {
+        let model: Model3Json = serde_json::from_str(
+            r#"{"FileReferences": {"Expressions": [{"File": "exp/smile.exp3.json"}]}}"#,
+        )
+        .unwrap();
+        let expressions = model.file_references.expressions.unwrap();
+        assert_eq!(expressions[0].name, "");
+        assert_eq!(expressions[0].file, "exp/smile.exp3.json");
+    }
+
+    #[test]
+    fn moc3_canvas_size_is_read_from_the_canvas_info() {
+        let path = std::env::temp_dir().join(format!(
+            "live2d-desktop-pet-canvas-{}.moc3",
+            std::process::id()
+        ));
+        let canvas_info = 160_u32;
+        let mut bytes = vec![0_u8; canvas_info as usize + 24];
+        bytes[..4].copy_from_slice(MOC3_MAGIC);
+        bytes[68..72].copy_from_slice(&canvas_info.to_le_bytes());
+        for (index, value) in [1000.0_f32, 640.0, 900.0, 1280.0, 1800.0]
+            .iter()
+            .enumerate()
+        {
+            let offset = canvas_info as usize + index * 4;
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(read_moc3_canvas(&path), Some((1280.0, 1800.0)));
+
+        bytes[..4].copy_from_slice(b"MOC2");
+        fs::write(&path, &bytes).unwrap();
+        assert_eq!(read_moc3_canvas(&path), None);
+
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::model_validate::{read_model3_json, resolve_reference};
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter};
+
+const WARM_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Bumped per `warm_model`; a warm whose token is stale stops at the next chunk.
+static WARM_TOKEN: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelWarmedPayload {
+    path: String,
+    files: usize,
+    bytes: u64,
+}
+
+fn warm_targets(model_path: &Path) -> Result<Vec<PathBuf>, String> {
+    let references = read_model3_json(model_path)?.file_references;
+    let model_dir = model_path.parent().unwrap_or_else(|| Path::new("."));
+    Ok(references
+        .moc
+        .iter()
+        .chain(references.textures.iter())
+        .map(|declared| resolve_reference(model_dir, declared))
+        .collect())
+}
+
+/// Reads `path` to the end, discarding the data. Returns `None` if cancelled.
+fn read_through(path: &Path, token: u64, buffer: &mut [u8]) -> Option<u64> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            tracing::debug!("not warming {}: {error}", path.display());
+            return Some(0);
+        }
+    };
+
+    let mut total = 0;
+    loop {
+        if WARM_TOKEN.load(Ordering::SeqCst) != token {
+            return None;
+        }
+        match file.read(buffer) {
+            Ok(0) => return Some(total),
+            Ok(read) => total += read as u64,
+            Err(error) => {
+                tracing::debug!("stopped warming {}: {error}", path.display());
+                return Some(total);
+            }
+        }
+    }
+}
+
+/// Reads the model's moc3 and textures on a background thread so the OS page
+/// cache already holds them when the renderer loads the model, then emits
+/// `model-warmed`. A newer call cancels any warm still in progress.
+#[tauri::command]
+pub fn warm_model(app: AppHandle, path: String) -> Result<(), String> {
+    let path = path.trim().to_string();
+    let targets = warm_targets(Path::new(&path))?;
+    let token = WARM_TOKEN.fetch_add(1, Ordering::SeqCst) + 1;
+
+    std::thread::Builder::new()
+        .name("model-warm".to_string())
+        .spawn(move || {
+            let mut buffer = vec![0_u8; WARM_CHUNK_BYTES];
+            let mut bytes = 0;
+            for target in &targets {
+                match read_through(target, token, &mut buffer) {
+                    Some(read) => bytes += read,
+                    None => {
+                        tracing::debug!("cancelled warming {path}");
+                        return;
+                    }
+                }
+            }
+
+            let payload = ModelWarmedPayload {
+                path,
+                files: targets.len(),
+                bytes,
+            };
+            let _ = app.emit("model-warmed", payload);
+        })
+        .map_err(|error| format!("failed to start model warm thread: {error}"))?;
+    Ok(())
+}
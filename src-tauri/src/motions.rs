@@ -0,0 +1,72 @@
+use crate::library::active_model_path;
+use crate::model_validate::list_model_animations;
+use crate::reaction_cooldown::allow_reaction;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PlayMotionPayload {
+    group: String,
+    index: usize,
+    file: String,
+}
+
+/// Motion files of `group` in the active model, in model3.json order.
+fn motion_group_files(app: &AppHandle, group: &str) -> Result<Vec<String>, String> {
+    let model_path = active_model_path(app).ok_or_else(|| "no active model".to_string())?;
+    let mut animations = list_model_animations(model_path)?;
+    animations
+        .motion_groups
+        .remove(group)
+        .ok_or_else(|| format!("motion group {group} not found in the active model"))
+}
+
+/// Emits `play-motion` and returns `true`, or returns `false` without emitting
+/// when the group played within the reaction cooldown.
+fn play_motion(app: &AppHandle, group: String, index: usize, file: String) -> Result<bool, String> {
+    if !allow_reaction(&format!("play-motion:{group}")) {
+        return Ok(false);
+    }
+    app.emit_to(
+        "main",
+        "play-motion",
+        PlayMotionPayload { group, index, file },
+    )
+    .map_err(|error| error.to_string())?;
+    Ok(true)
+}
+
+/// Plays motion `index` of `group`. `false` means the reaction cooldown held
+/// it back.
+#[tauri::command]
+pub fn trigger_motion(app: AppHandle, group: String, index: usize) -> Result<bool, String> {
+    let files = motion_group_files(&app, &group)?;
+    let file = files.get(index).cloned().ok_or_else(|| {
+        format!(
+            "motion index {index} out of range for group {group} ({} motions)",
+            files.len()
+        )
+    })?;
+    play_motion(&app, group, index, file)
+}
+
+/// Plays a random motion from `group` and returns the chosen index, or `None`
+/// when the reaction cooldown held it back.
+#[tauri::command]
+pub fn random_motion(app: AppHandle, group: String) -> Result<Option<usize>, String> {
+    let mut files = motion_group_files(&app, &group)?;
+    if files.is_empty() {
+        return Err(format!("motion group {group} has no motions"));
+    }
+
+    // Clock jitter is plenty for picking an idle animation.
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.subsec_nanos() as usize)
+        .unwrap_or(0);
+    let index = seed % files.len();
+    let file = files.swap_remove(index);
+    Ok(play_motion(&app, group, index, file)?.then_some(index))
+}
@@ -0,0 +1,156 @@
+use crate::UiState;
+use serde::Serialize;
+use std::sync::{atomic::Ordering, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const PERMISSIONS_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+static LAST_PERMISSIONS: Mutex<Option<PermissionsStatus>> = Mutex::new(None);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum PermissionState {
+    Granted,
+    Denied,
+    NotRequired,
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionsStatus {
+    /// macOS Input Monitoring, needed to receive global key events.
+    pub input_monitoring: PermissionState,
+    /// macOS Accessibility, needed by rdev's event tap on older macOS releases.
+    pub accessibility: PermissionState,
+    /// Linux: global capture only works on an X11 (or XWayland) display.
+    pub display_server: PermissionState,
+    pub platform: String,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionOutcome {
+    pub status: PermissionState,
+    /// Whether the Input Monitoring pane was opened for the user.
+    pub opened: bool,
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::PermissionState;
+
+    // IOHIDRequestType / IOHIDAccessType from IOKit/hidsystem/IOHIDLib.h.
+    const K_IOHID_REQUEST_TYPE_LISTEN_EVENT: u32 = 1;
+    const K_IOHID_ACCESS_TYPE_GRANTED: u32 = 0;
+    const K_IOHID_ACCESS_TYPE_DENIED: u32 = 1;
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOHIDCheckAccess(request_type: u32) -> u32;
+    }
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXIsProcessTrusted() -> bool;
+    }
+
+    pub fn input_monitoring() -> PermissionState {
+        // SAFETY: IOHIDCheckAccess only reads the TCC database; it has no preconditions.
+        match unsafe { IOHIDCheckAccess(K_IOHID_REQUEST_TYPE_LISTEN_EVENT) } {
+            K_IOHID_ACCESS_TYPE_GRANTED => PermissionState::Granted,
+            K_IOHID_ACCESS_TYPE_DENIED => PermissionState::Denied,
+            _ => PermissionState::Unknown,
+        }
+    }
+
+    pub fn accessibility() -> PermissionState {
+        // SAFETY: AXIsProcessTrusted takes no arguments and only queries TCC.
+        if unsafe { AXIsProcessTrusted() } {
+            PermissionState::Granted
+        } else {
+            PermissionState::Denied
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn query_permissions() -> PermissionsStatus {
+    PermissionsStatus {
+        input_monitoring: macos::input_monitoring(),
+        accessibility: macos::accessibility(),
+        display_server: PermissionState::NotRequired,
+        platform: std::env::consts::OS.to_string(),
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn query_permissions() -> PermissionsStatus {
+    let display_server = if cfg!(target_os = "linux") {
+        match (
+            std::env::var_os("DISPLAY").is_some(),
+            std::env::var_os("WAYLAND_DISPLAY").is_some(),
+        ) {
+            (true, _) => PermissionState::Granted,
+            (false, true) => PermissionState::Denied,
+            (false, false) => PermissionState::Unknown,
+        }
+    } else {
+        PermissionState::NotRequired
+    };
+
+    PermissionsStatus {
+        input_monitoring: PermissionState::NotRequired,
+        accessibility: PermissionState::NotRequired,
+        display_server,
+        platform: std::env::consts::OS.to_string(),
+    }
+}
+
+/// Re-queries permissions and emits `permissions-changed` when anything flipped.
+pub(crate) fn refresh_permissions(app: &AppHandle) -> PermissionsStatus {
+    let status = query_permissions();
+    let Ok(mut last) = LAST_PERMISSIONS.lock() else {
+        return status;
+    };
+
+    if last.as_ref().is_some_and(|previous| previous != &status) {
+        let _ = app.emit("permissions-changed", status.clone());
+    }
+    *last = Some(status.clone());
+    status
+}
+
+/// Polls so a grant made in System Settings reaches the UI without a restart.
+pub(crate) fn spawn_permissions_watcher(app: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("permissions-watcher".to_string())
+        .spawn(move || loop {
+            if app.state::<UiState>().quitting.load(Ordering::SeqCst) {
+                break;
+            }
+            refresh_permissions(&app);
+            std::thread::sleep(PERMISSIONS_POLL_INTERVAL);
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start permissions watcher: {error}");
+    }
+}
+
+#[tauri::command]
+pub fn get_permissions_status(app: AppHandle) -> PermissionsStatus {
+    refresh_permissions(&app)
+}
+
+/// Checks the permission global input needs and, when macOS reports it denied,
+/// opens the Input Monitoring pane. Other states and platforms have no side effects.
+#[tauri::command]
+pub fn ensure_input_permission(app: AppHandle) -> PermissionOutcome {
+    let status = refresh_permissions(&app).input_monitoring;
+    let opened = status == PermissionState::Denied
+        && crate::open_input_monitoring_settings()
+            .map_err(|error| tracing::warn!("failed to open Input Monitoring settings: {error}"))
+            .is_ok();
+    PermissionOutcome { status, opened }
+}
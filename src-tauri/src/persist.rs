@@ -0,0 +1,99 @@
+use crate::storage_root::store_path;
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+// Shared with the frontend store in `src/lib/settings.ts`.
+pub const STORE_FILE: &str = "pet-settings.json";
+
+pub fn load_value<T: DeserializeOwned>(app: &AppHandle, key: &str) -> Option<T> {
+    let store = match app.store(store_path(app)) {
+        Ok(store) => store,
+        Err(error) => {
+            tracing::warn!("failed to open store {STORE_FILE}: {error}");
+            return None;
+        }
+    };
+
+    let value = store.get(key)?;
+    match serde_json::from_value(value) {
+        Ok(parsed) => Some(parsed),
+        Err(error) => {
+            tracing::warn!("ignoring malformed store value for {key}: {error}");
+            None
+        }
+    }
+}
+
+pub fn save_value<T: Serialize>(app: &AppHandle, key: &str, value: &T) -> Result<(), String> {
+    let store = app
+        .store(store_path(app))
+        .map_err(|error| format!("failed to open store {STORE_FILE}: {error}"))?;
+    let json = serde_json::to_value(value)
+        .map_err(|error| format!("failed to serialize {key}: {error}"))?;
+
+    store.set(key, json);
+    store
+        .save()
+        .map_err(|error| format!("failed to save store {STORE_FILE}: {error}"))
+}
+
+/// Removes every key except those in `keep` and saves. Returns the removed keys.
+pub fn clear_except(app: &AppHandle, keep: &[String]) -> Result<Vec<String>, String> {
+    let store = app
+        .store(store_path(app))
+        .map_err(|error| format!("failed to open store {STORE_FILE}: {error}"))?;
+
+    let removed: Vec<String> = store
+        .keys()
+        .into_iter()
+        .filter(|key| !keep.contains(key))
+        .collect();
+    for key in &removed {
+        store.delete(key);
+    }
+    store
+        .save()
+        .map_err(|error| format!("failed to save store {STORE_FILE}: {error}"))?;
+    Ok(removed)
+}
+
+/// Every key and value currently in the store.
+pub fn entries(app: &AppHandle) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let store = app
+        .store(store_path(app))
+        .map_err(|error| format!("failed to open store {STORE_FILE}: {error}"))?;
+    Ok(store.entries().into_iter().collect())
+}
+
+/// Writes `values` into the store and saves. Without `merge` every other key
+/// is removed first. Returns the keys that were written or removed.
+pub fn replace_entries(
+    app: &AppHandle,
+    values: serde_json::Map<String, serde_json::Value>,
+    merge: bool,
+) -> Result<Vec<String>, String> {
+    let store = app
+        .store(store_path(app))
+        .map_err(|error| format!("failed to open store {STORE_FILE}: {error}"))?;
+
+    let mut changed: Vec<String> = Vec::new();
+    if !merge {
+        changed = store
+            .keys()
+            .into_iter()
+            .filter(|key| !values.contains_key(key))
+            .collect();
+        for key in &changed {
+            store.delete(key);
+        }
+    }
+    for (key, value) in values {
+        store.set(key.clone(), value);
+        changed.push(key);
+    }
+    store
+        .save()
+        .map_err(|error| format!("failed to save store {STORE_FILE}: {error}"))?;
+    Ok(changed)
+}
@@ -0,0 +1,101 @@
+use crate::diagnostics::SharedDiagnosticsState;
+use crate::storage_root::store_path;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_store::StoreExt;
+use tauri_plugin_updater::UpdaterExt;
+
+/// Outcome of each plugin's startup probe, in registration order.
+static PLUGIN_STATUS: Mutex<Vec<PluginStatus>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginStatus {
+    pub name: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn managed<T: Send + Sync + 'static>(app: &AppHandle) -> Result<(), String> {
+    app.try_state::<T>()
+        .map(|_| ())
+        .ok_or_else(|| "plugin state is not registered".to_string())
+}
+
+/// A plugin that fails its own setup aborts startup, so what can go wrong
+/// afterwards is its first real use. Each probe does the cheapest such use.
+fn probe(app: &AppHandle, name: &str) -> Result<(), String> {
+    match name {
+        "autostart" => app
+            .autolaunch()
+            .is_enabled()
+            .map(|_| ())
+            .map_err(|error| error.to_string()),
+        "dialog" => managed::<tauri_plugin_dialog::Dialog<Wry>>(app),
+        "global-shortcut" => managed::<tauri_plugin_global_shortcut::GlobalShortcut<Wry>>(app),
+        "opener" => managed::<tauri_plugin_opener::Opener<Wry>>(app),
+        "store" => app
+            .store(store_path(app))
+            .map(|_| ())
+            .map_err(|error| error.to_string()),
+        "updater" => app.updater().map(|_| ()).map_err(|error| error.to_string()),
+        _ => Err("unknown plugin".to_string()),
+    }
+}
+
+/// Probes every plugin registered in `run` once at startup, logging and
+/// recording failures as diagnostics.
+pub(crate) fn probe_plugins(app: &AppHandle) {
+    let statuses: Vec<PluginStatus> = [
+        "autostart",
+        "dialog",
+        "global-shortcut",
+        "opener",
+        "store",
+        "updater",
+    ]
+    .into_iter()
+    .map(|name| {
+        let result = probe(app, name);
+        if let Err(error) = &result {
+            tracing::error!("{name} plugin failed to initialize: {error}");
+            app.state::<SharedDiagnosticsState>().record_error(
+                "error".to_string(),
+                format!("{name} plugin failed to initialize: {error}"),
+                Some("plugins".to_string()),
+            );
+        }
+        PluginStatus {
+            name: name.to_string(),
+            ok: result.is_ok(),
+            error: result.err(),
+        }
+    })
+    .collect();
+
+    if let Ok(mut status) = PLUGIN_STATUS.lock() {
+        *status = statuses;
+    }
+}
+
+/// The startup probe error for `name`, if it failed.
+pub(crate) fn plugin_error(name: &str) -> Option<String> {
+    let status = PLUGIN_STATUS.lock().ok()?;
+    status
+        .iter()
+        .find(|plugin| plugin.name == name)
+        .and_then(|plugin| plugin.error.clone())
+}
+
+/// Which plugins came up at startup, so e.g. a broken autostart reads as the
+/// plugin failing rather than as the setting not sticking.
+#[tauri::command]
+pub fn get_plugin_status() -> Vec<PluginStatus> {
+    PLUGIN_STATUS
+        .lock()
+        .map(|status| status.clone())
+        .unwrap_or_default()
+}
@@ -0,0 +1,143 @@
+use crate::input_listener::{emit_tuning_changes, ForwarderPollTimings, SharedInputListenerState};
+use crate::persist::{load_value, save_value};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+const TUNING_PRESET_KEY: &str = "tuningPreset";
+// Read by the renderer, which applies it to the Pixi ticker.
+const FPS_CAP_KEY: &str = "fpsCap";
+
+/// A named bundle of the performance knobs, so users can pick a trade-off
+/// instead of tuning throttles and poll intervals one by one.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TuningPreset {
+    pub name: &'static str,
+    pub label: &'static str,
+    pub description: &'static str,
+    /// `None` renders at the display's refresh rate.
+    pub fps_cap: Option<u32>,
+    pub mouse_move_throttle_ms: u64,
+    pub forwarder_poll: ForwarderPollTimings,
+    pub adaptive_throttle: bool,
+}
+
+const PRESETS: [TuningPreset; 2] = [
+    TuningPreset {
+        name: "performance",
+        label: "Performance",
+        description: "Caps rendering at 30 FPS and forwards pointer moves less often. \
+                      Best on laptops and older machines.",
+        fps_cap: Some(30),
+        mouse_move_throttle_ms: 33,
+        forwarder_poll: ForwarderPollTimings {
+            active_ms: 8,
+            idle_ms: 250,
+        },
+        adaptive_throttle: true,
+    },
+    TuningPreset {
+        name: "quality",
+        label: "Quality",
+        description: "Uncapped rendering and the most responsive input tracking. \
+                      Uses more CPU.",
+        fps_cap: None,
+        mouse_move_throttle_ms: 8,
+        forwarder_poll: ForwarderPollTimings {
+            active_ms: 2,
+            idle_ms: 40,
+        },
+        adaptive_throttle: false,
+    },
+];
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresetChoice {
+    #[serde(flatten)]
+    pub preset: TuningPreset,
+    /// This was the last preset applied.
+    pub active: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FpsCapChangedPayload {
+    fps: Option<u32>,
+}
+
+fn emit_logged<S: Serialize + Clone>(app: &AppHandle, event: &str, payload: S) {
+    if let Err(error) = app.emit(event, payload) {
+        tracing::warn!("failed to emit {event} event: {error}");
+    }
+}
+
+/// The FPS cap the renderer should apply; `None` when uncapped or never set.
+#[tauri::command]
+pub fn get_fps_cap(app: AppHandle) -> Option<u32> {
+    load_value::<Option<u32>>(&app, FPS_CAP_KEY).flatten()
+}
+
+#[tauri::command]
+pub fn list_presets(app: AppHandle) -> Vec<PresetChoice> {
+    let active: Option<String> = load_value(&app, TUNING_PRESET_KEY);
+    PRESETS
+        .iter()
+        .map(|preset| PresetChoice {
+            preset: *preset,
+            active: active.as_deref() == Some(preset.name),
+        })
+        .collect()
+}
+
+/// Re-applies the listener settings of the last applied preset at startup;
+/// they are not persisted on their own. The FPS cap is read by the renderer.
+pub(crate) fn restore_tuning_preset(app: &AppHandle) {
+    let Some(name) = load_value::<Option<String>>(app, TUNING_PRESET_KEY).flatten() else {
+        return;
+    };
+    let Some(preset) = PRESETS.iter().find(|preset| preset.name == name) else {
+        tracing::warn!("ignoring unknown saved tuning preset {name}");
+        return;
+    };
+    let applied = app.state::<SharedInputListenerState>().apply_tuning(
+        preset.mouse_move_throttle_ms,
+        preset.forwarder_poll,
+        preset.adaptive_throttle,
+    );
+    if let Err(error) = applied {
+        tracing::warn!("failed to restore tuning preset {name}: {error}");
+    }
+}
+
+/// Applies every setting in the named preset, then emits a change event for
+/// each one that actually changed.
+#[tauri::command]
+pub fn apply_preset(app: AppHandle, name: String) -> Result<TuningPreset, String> {
+    let name = name.trim().to_lowercase();
+    let preset = *PRESETS
+        .iter()
+        .find(|preset| preset.name == name)
+        .ok_or_else(|| format!("unknown preset: {name}"))?;
+
+    let listener = app.state::<SharedInputListenerState>();
+    let before = listener.config();
+    let fps_cap_before = get_fps_cap(app.clone());
+
+    listener.apply_tuning(
+        preset.mouse_move_throttle_ms,
+        preset.forwarder_poll,
+        preset.adaptive_throttle,
+    )?;
+    save_value(&app, FPS_CAP_KEY, &preset.fps_cap)?;
+    save_value(&app, TUNING_PRESET_KEY, &preset.name)?;
+
+    emit_tuning_changes(&app, &before, &listener.config());
+    if fps_cap_before != preset.fps_cap {
+        let payload = FpsCapChangedPayload {
+            fps: preset.fps_cap,
+        };
+        emit_logged(&app, "fps-cap-changed", payload);
+    }
+    Ok(preset)
+}
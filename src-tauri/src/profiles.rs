@@ -0,0 +1,315 @@
+use crate::diagnostics::now_timestamp_ms;
+use crate::model_scan::model_path_exists;
+use crate::persist::{load_value, save_value};
+use crate::window_state::WindowConfig;
+use crate::{main_window, UiState, GHOST_OPACITY};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition};
+
+const PROFILES_KEY: &str = "profiles";
+const MAX_PROFILE_NAME_LEN: usize = 64;
+const PROFILE_FILE_VERSION: u32 = 1;
+const MAX_PROFILE_FILE_BYTES: u64 = 1024 * 1024;
+
+// Frontend-owned pet settings keys (see `src/lib/settings.ts`).
+const MODEL_PATH_KEY: &str = "modelPath";
+const SCALE_KEY: &str = "scale";
+const POS_KEY: &str = "pos";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PetProfile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_path: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pos: Option<JsonValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_x: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window_y: Option<i32>,
+    #[serde(default)]
+    pub click_through: bool,
+    #[serde(default = "default_true")]
+    pub locked: bool,
+    #[serde(default = "default_true")]
+    pub snap_enabled: bool,
+    #[serde(default)]
+    pub do_not_disturb: bool,
+    #[serde(default)]
+    pub ghost_mode: bool,
+    /// Follows from `ghost_mode`; ignored when the profile is loaded.
+    #[serde(default = "full_opacity")]
+    pub opacity: f64,
+    #[serde(default)]
+    pub saved_at: u64,
+    /// Set on import when the referenced model is missing on this machine.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_relink: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileFile {
+    version: u32,
+    name: String,
+    profile: PetProfile,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedProfile {
+    pub name: String,
+    pub profile: PetProfile,
+    pub warnings: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn full_opacity() -> f64 {
+    1.0
+}
+
+pub(crate) fn normalize_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty.".to_string());
+    }
+    if name.chars().count() > MAX_PROFILE_NAME_LEN {
+        return Err(format!(
+            "Profile name is longer than {MAX_PROFILE_NAME_LEN} characters."
+        ));
+    }
+    Ok(name.to_string())
+}
+
+pub(crate) fn load_profiles(app: &AppHandle) -> BTreeMap<String, PetProfile> {
+    load_value(app, PROFILES_KEY).unwrap_or_default()
+}
+
+pub(crate) fn save_profiles(
+    app: &AppHandle,
+    profiles: &BTreeMap<String, PetProfile>,
+) -> Result<(), String> {
+    save_value(app, PROFILES_KEY, profiles)
+}
+
+fn capture_profile(app: &AppHandle) -> PetProfile {
+    let state = app.state::<UiState>();
+    let position = main_window(app, "save_profile")
+        .and_then(|window| window.outer_position().map_err(|error| error.to_string()))
+        .map_err(|error| tracing::warn!("profile saved without window position: {error}"))
+        .ok();
+    let ghost_mode = state.ghost_mode.load(Ordering::SeqCst);
+    // Ghost mode forces click-through and the lock, so save what it restores.
+    let ghost_restore = if ghost_mode {
+        state.ghost_restore.lock().ok().and_then(|restore| *restore)
+    } else {
+        None
+    };
+
+    PetProfile {
+        model_path: load_value(app, MODEL_PATH_KEY),
+        scale: load_value(app, SCALE_KEY),
+        pos: load_value(app, POS_KEY),
+        window_x: position.map(|position| position.x),
+        window_y: position.map(|position| position.y),
+        click_through: ghost_restore.map_or_else(
+            || state.click_through.load(Ordering::SeqCst),
+            |restore| restore.click_through,
+        ),
+        locked: ghost_restore.map_or_else(
+            || state.locked.load(Ordering::SeqCst),
+            |restore| restore.locked,
+        ),
+        snap_enabled: state.snap_enabled.load(Ordering::SeqCst),
+        do_not_disturb: state.do_not_disturb.load(Ordering::SeqCst),
+        ghost_mode,
+        opacity: if ghost_mode { GHOST_OPACITY } else { 1.0 },
+        saved_at: now_timestamp_ms(),
+        needs_relink: false,
+    }
+}
+
+pub(crate) fn apply_profile(
+    app: &AppHandle,
+    name: String,
+    profile: PetProfile,
+) -> Result<AppliedProfile, String> {
+    let mut warnings = Vec::new();
+
+    match profile.model_path.as_deref() {
+        Some(model_path) if !model_path_exists(model_path) => {
+            warnings.push(format!(
+                "Model file no longer exists, keeping the current model: {model_path}"
+            ));
+        }
+        Some(model_path) => save_value(app, MODEL_PATH_KEY, &model_path)?,
+        None => {}
+    }
+    if let Some(scale) = profile.scale {
+        save_value(app, SCALE_KEY, &scale)?;
+    }
+    if let Some(pos) = profile.pos.as_ref() {
+        save_value(app, POS_KEY, pos)?;
+    }
+    crate::library::emit_pet_settings_updated(app);
+
+    if let (Some(x), Some(y)) = (profile.window_x, profile.window_y) {
+        if let Err(error) = main_window(app, "load_profile").and_then(|window| {
+            window
+                .set_position(PhysicalPosition::new(x, y))
+                .map_err(|error| error.to_string())
+        }) {
+            warnings.push(format!("Failed to restore window position: {error}"));
+        }
+    }
+
+    crate::window_state::apply_window_config_internal(
+        app,
+        &WindowConfig {
+            click_through: Some(profile.click_through),
+            locked: Some(profile.locked),
+            snap_enabled: Some(profile.snap_enabled),
+            ghost_mode: Some(profile.ghost_mode),
+            ..WindowConfig::default()
+        },
+    )?;
+    let state = app.state::<UiState>();
+    crate::set_do_not_disturb_internal(app, &state, profile.do_not_disturb)?;
+
+    for warning in &warnings {
+        tracing::warn!("profile {name}: {warning}");
+    }
+
+    let applied = AppliedProfile {
+        name,
+        profile,
+        warnings,
+    };
+    let _ = app.emit("profile-applied", applied.clone());
+    Ok(applied)
+}
+
+#[tauri::command]
+pub fn save_profile(app: AppHandle, name: String) -> Result<PetProfile, String> {
+    let name = normalize_profile_name(&name)?;
+    let profile = capture_profile(&app);
+
+    let mut profiles = load_profiles(&app);
+    profiles.insert(name, profile.clone());
+    save_profiles(&app, &profiles)?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub fn load_profile(app: AppHandle, name: String) -> Result<AppliedProfile, String> {
+    let name = normalize_profile_name(&name)?;
+    let profile = load_profiles(&app)
+        .remove(&name)
+        .ok_or_else(|| format!("Profile not found: {name}"))?;
+    apply_profile(&app, name, profile)
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Vec<String> {
+    load_profiles(&app).into_keys().collect()
+}
+
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<bool, String> {
+    let name = normalize_profile_name(&name)?;
+    let mut profiles = load_profiles(&app);
+    let removed = profiles.remove(&name).is_some();
+    if removed {
+        save_profiles(&app, &profiles)?;
+    }
+    Ok(removed)
+}
+
+#[tauri::command]
+pub fn export_profile(app: AppHandle, name: String, dest: String) -> Result<String, String> {
+    let name = normalize_profile_name(&name)?;
+    let profile = load_profiles(&app)
+        .remove(&name)
+        .ok_or_else(|| format!("Profile not found: {name}"))?;
+
+    let file = ProfileFile {
+        version: PROFILE_FILE_VERSION,
+        name,
+        profile,
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|error| format!("failed to serialize profile: {error}"))?;
+    let dest = PathBuf::from(dest);
+    fs::write(&dest, json)
+        .map_err(|error| format!("failed to write {}: {error}", dest.display()))?;
+    Ok(dest.to_string_lossy().to_string())
+}
+
+fn unique_profile_name(profiles: &BTreeMap<String, PetProfile>, name: String) -> String {
+    if !profiles.contains_key(&name) {
+        return name;
+    }
+    (2..)
+        .map(|suffix| {
+            // Shorten the base so the suffixed name still passes
+            // `normalize_profile_name`.
+            let suffix = format!(" ({suffix})");
+            let keep = MAX_PROFILE_NAME_LEN.saturating_sub(suffix.chars().count());
+            let base: String = name.chars().take(keep).collect();
+            format!("{}{suffix}", base.trim_end())
+        })
+        .find(|candidate| !profiles.contains_key(candidate))
+        .unwrap_or(name)
+}
+
+/// Imports a profile file and returns the stored name, which gets a numeric
+/// suffix if a profile with the same name already exists.
+#[tauri::command]
+pub fn import_profile(app: AppHandle, src: String) -> Result<String, String> {
+    let src = PathBuf::from(src);
+    let size = fs::metadata(&src)
+        .map_err(|error| format!("failed to read {}: {error}", src.display()))?
+        .len();
+    if size > MAX_PROFILE_FILE_BYTES {
+        return Err(format!(
+            "{} is too large to be a profile file.",
+            src.display()
+        ));
+    }
+
+    let raw = fs::read_to_string(&src)
+        .map_err(|error| format!("failed to read {}: {error}", src.display()))?;
+    let file: ProfileFile =
+        serde_json::from_str(&raw).map_err(|error| format!("Not a valid profile file: {error}"))?;
+    if file.version > PROFILE_FILE_VERSION {
+        return Err(format!(
+            "Profile file version {} is newer than this app supports.",
+            file.version
+        ));
+    }
+
+    let mut profile = file.profile;
+    profile.needs_relink = profile
+        .model_path
+        .as_deref()
+        .is_some_and(|model_path| !model_path_exists(model_path));
+    if profile.needs_relink {
+        tracing::warn!("imported profile references a missing model; relink required");
+    }
+
+    let mut profiles = load_profiles(&app);
+    let name = unique_profile_name(&profiles, normalize_profile_name(&file.name)?);
+    profiles.insert(name.clone(), profile);
+    save_profiles(&app, &profiles)?;
+    Ok(name)
+}
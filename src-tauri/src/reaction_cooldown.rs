@@ -0,0 +1,115 @@
+use crate::persist::{load_value, save_value};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+const REACTION_COOLDOWN_KEY: &str = "reactionCooldownMs";
+const MAX_REACTION_COOLDOWN_MS: u64 = 60_000;
+
+/// Minimum time between two firings of the same reaction; 0 disables the gate.
+static REACTION_COOLDOWN_MS: AtomicU64 = AtomicU64::new(0);
+static REACTION_GATE: Lazy<Mutex<ReactionGate>> = Lazy::new(Mutex::default);
+
+/// When each reaction id last fired. Only firings that were let through count,
+/// so a steady burst still fires once per cooldown.
+#[derive(Default)]
+struct ReactionGate {
+    last_fired: HashMap<String, Instant>,
+}
+
+impl ReactionGate {
+    fn allow(&mut self, id: &str, now: Instant, cooldown: Duration) -> bool {
+        if let Some(last) = self.last_fired.get(id) {
+            if now.saturating_duration_since(*last) < cooldown {
+                return false;
+            }
+        }
+        // Entries older than the cooldown can no longer block anything.
+        self.last_fired
+            .retain(|_, last| now.saturating_duration_since(*last) < cooldown);
+        self.last_fired.insert(id.to_string(), now);
+        true
+    }
+}
+
+/// Whether reaction `id` (e.g. `"play-motion:Idle"`) may fire now. Records the
+/// firing when it may, so callers must emit right after a `true`.
+pub(crate) fn allow_reaction(id: &str) -> bool {
+    let cooldown = REACTION_COOLDOWN_MS.load(Ordering::SeqCst);
+    if cooldown == 0 {
+        return true;
+    }
+    let allowed = REACTION_GATE
+        .lock()
+        .map(|mut gate| gate.allow(id, Instant::now(), Duration::from_millis(cooldown)))
+        .unwrap_or(true);
+    if !allowed {
+        tracing::debug!("reaction {id} suppressed by the {cooldown} ms cooldown");
+    }
+    allowed
+}
+
+pub(crate) fn restore_reaction_cooldown(app: &AppHandle) {
+    let saved: Option<u64> = load_value::<Option<u64>>(app, REACTION_COOLDOWN_KEY).flatten();
+    REACTION_COOLDOWN_MS.store(
+        saved.unwrap_or(0).min(MAX_REACTION_COOLDOWN_MS),
+        Ordering::SeqCst,
+    );
+}
+
+#[tauri::command]
+pub fn get_reaction_cooldown_ms() -> u64 {
+    REACTION_COOLDOWN_MS.load(Ordering::SeqCst)
+}
+
+/// Sets the minimum interval between repeats of the same reaction, clamped to
+/// 60 s; 0 turns the cooldown off. It gates `play-motion`, `hotkey-triggered`,
+/// pointer `drag-start`/`drag-end` and `pet-dropped`. Window drags always emit
+/// `drag-start`/`drag-end`, which the pet window relies on to defer snapping,
+/// and a shortcut's toggle action (visibility, click-through, lock) still runs
+/// on every press; only its `hotkey-triggered` event is held back.
+#[tauri::command]
+pub fn set_reaction_cooldown_ms(app: AppHandle, ms: u64) -> Result<u64, String> {
+    let ms = ms.min(MAX_REACTION_COOLDOWN_MS);
+    REACTION_COOLDOWN_MS.store(ms, Ordering::SeqCst);
+    if ms == 0 {
+        if let Ok(mut gate) = REACTION_GATE.lock() {
+            gate.last_fired.clear();
+        }
+    }
+    save_value(&app, REACTION_COOLDOWN_KEY, &Some(ms))?;
+    Ok(ms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_inside_the_cooldown_are_suppressed_per_id() {
+        let mut gate = ReactionGate::default();
+        let cooldown = Duration::from_millis(500);
+        let start = Instant::now();
+
+        assert!(gate.allow("play-motion:Tap", start, cooldown));
+        assert!(!gate.allow(
+            "play-motion:Tap",
+            start + Duration::from_millis(200),
+            cooldown
+        ));
+        assert!(gate.allow(
+            "play-motion:Idle",
+            start + Duration::from_millis(200),
+            cooldown
+        ));
+        // Measured from the last allowed firing, not the suppressed one.
+        assert!(gate.allow(
+            "play-motion:Tap",
+            start + Duration::from_millis(500),
+            cooldown
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use crate::diagnostics::SharedDiagnosticsState;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInfo {
+    pub remote: bool,
+    /// `"rdp"`, `"ssh"`, `"x11-forwarded"`, `"console"`, `"wayland"`, `"x11"`,
+    /// `"tty"`, or `"unknown"` when the platform gives no reliable answer.
+    pub session_type: String,
+}
+
+impl SessionInfo {
+    fn new(remote: bool, session_type: &str) -> Self {
+        Self {
+            remote,
+            session_type: session_type.to_string(),
+        }
+    }
+}
+
+fn env_is_set(name: &str) -> bool {
+    std::env::var_os(name).is_some_and(|value| !value.is_empty())
+}
+
+fn ssh_session() -> bool {
+    env_is_set("SSH_CONNECTION") || env_is_set("SSH_CLIENT") || env_is_set("SSH_TTY")
+}
+
+#[cfg(target_os = "windows")]
+fn detect_session() -> SessionInfo {
+    const SM_REMOTESESSION: i32 = 0x1000;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetSystemMetrics(index: i32) -> i32;
+    }
+
+    // SAFETY: GetSystemMetrics only reads a system value.
+    if unsafe { GetSystemMetrics(SM_REMOTESESSION) } != 0 {
+        return SessionInfo::new(true, "rdp");
+    }
+    if ssh_session() {
+        return SessionInfo::new(true, "ssh");
+    }
+    SessionInfo::new(false, "console")
+}
+
+#[cfg(target_os = "macos")]
+fn detect_session() -> SessionInfo {
+    if ssh_session() {
+        return SessionInfo::new(true, "ssh");
+    }
+    // Screen Sharing and other VNC servers attach to the console session, so
+    // there is nothing to tell them apart by.
+    SessionInfo::new(false, "unknown")
+}
+
+#[cfg(target_os = "linux")]
+fn detect_session() -> SessionInfo {
+    if ssh_session() {
+        // `ssh -X` sets DISPLAY to a forwarded `host:N` display.
+        let forwarded = std::env::var("DISPLAY")
+            .is_ok_and(|display| !display.is_empty() && !display.starts_with(':'));
+        let session_type = if forwarded { "x11-forwarded" } else { "ssh" };
+        return SessionInfo::new(true, session_type);
+    }
+    match std::env::var("XDG_SESSION_TYPE").as_deref() {
+        Ok("wayland") => SessionInfo::new(false, "wayland"),
+        Ok("x11") => SessionInfo::new(false, "x11"),
+        Ok("tty") => SessionInfo::new(false, "tty"),
+        _ if env_is_set("WAYLAND_DISPLAY") => SessionInfo::new(false, "wayland"),
+        _ if env_is_set("DISPLAY") => SessionInfo::new(false, "x11"),
+        _ => SessionInfo::new(false, "unknown"),
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn detect_session() -> SessionInfo {
+    SessionInfo::new(false, "unknown")
+}
+
+/// Leaves a diagnostics entry at startup when running remotely, so reports of
+/// input not working over RDP or SSH explain themselves.
+pub(crate) fn note_remote_session(app: &AppHandle) {
+    let session = detect_session();
+    if !session.remote {
+        return;
+    }
+    let message = format!(
+        "running in a remote {} session; global input capture and click-through may not work",
+        session.session_type
+    );
+    tracing::warn!("{message}");
+    app.state::<SharedDiagnosticsState>().record_error(
+        "warn".to_string(),
+        message,
+        Some("session".to_string()),
+    );
+}
+
+#[tauri::command]
+pub fn get_session_info() -> SessionInfo {
+    detect_session()
+}
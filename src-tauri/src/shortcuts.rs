@@ -0,0 +1,219 @@
+use crate::persist::{load_value, save_value};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{atomic::Ordering, Mutex};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+const SHORTCUTS_KEY: &str = "globalShortcuts";
+
+pub const ACTION_TOGGLE_VISIBILITY: &str = "toggle_visibility";
+pub const ACTION_TOGGLE_CLICK_THROUGH: &str = "toggle_click_through";
+pub const ACTION_TOGGLE_LOCK: &str = "toggle_lock";
+
+const SUPPORTED_ACTIONS: [&str; 3] = [
+    ACTION_TOGGLE_VISIBILITY,
+    ACTION_TOGGLE_CLICK_THROUGH,
+    ACTION_TOGGLE_LOCK,
+];
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeyTriggeredPayload {
+    action: String,
+    accelerator: String,
+}
+
+/// Action name -> accelerator string, as persisted in the store.
+#[derive(Default)]
+pub struct ShortcutBindings {
+    bindings: Mutex<HashMap<String, String>>,
+}
+
+fn parse_accelerator(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|error| format!("invalid accelerator {accelerator}: {error}"))
+}
+
+fn register_accelerator(app: &AppHandle, accelerator: &str) -> Result<(), String> {
+    let shortcut = parse_accelerator(accelerator)?;
+    let manager = app.global_shortcut();
+    if manager.is_registered(shortcut) {
+        return Err(format!(
+            "shortcut conflict: {accelerator} is already registered"
+        ));
+    }
+
+    manager
+        .register(shortcut)
+        .map_err(|error| format!("shortcut conflict: failed to register {accelerator}: {error}"))
+}
+
+fn unregister_accelerator(app: &AppHandle, accelerator: &str) {
+    let Ok(shortcut) = parse_accelerator(accelerator) else {
+        return;
+    };
+    if let Err(error) = app.global_shortcut().unregister(shortcut) {
+        tracing::warn!("failed to unregister shortcut {accelerator}: {error}");
+    }
+}
+
+fn run_action(app: &AppHandle, action: &str) -> Result<(), String> {
+    let state = app.state::<crate::UiState>();
+    match action {
+        ACTION_TOGGLE_VISIBILITY => crate::toggle_main_window_visibility(app).map(|_| ()),
+        ACTION_TOGGLE_CLICK_THROUGH => {
+            let next = !state.click_through.load(Ordering::SeqCst);
+            crate::set_click_through_internal(app, &state, next).map(|_| ())
+        }
+        ACTION_TOGGLE_LOCK => {
+            let next = !state.locked.load(Ordering::SeqCst);
+            crate::set_locked_internal(app, &state, next).map(|_| ())
+        }
+        _ => Err(format!("unknown shortcut action: {action}")),
+    }
+}
+
+pub fn handle_shortcut_event(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+
+    let bindings = app.state::<ShortcutBindings>();
+    let binding = {
+        let Ok(bindings) = bindings.bindings.lock() else {
+            return;
+        };
+        bindings
+            .iter()
+            .find(|(_, accelerator)| {
+                parse_accelerator(accelerator)
+                    .map(|candidate| candidate.id() == shortcut.id())
+                    .unwrap_or(false)
+            })
+            .map(|(action, accelerator)| (action.clone(), accelerator.clone()))
+    };
+
+    let Some((action, accelerator)) = binding else {
+        return;
+    };
+    // Every supported action is a toggle, so each press must run it or the
+    // state would drift from what the user pressed; only the
+    // `hotkey-triggered` reaction goes through the cooldown.
+    if let Err(error) = run_action(app, &action) {
+        tracing::error!("global shortcut action {action} failed: {error}");
+        crate::record_backend_error(app, format!("shortcut {action} failed: {error}"));
+        return;
+    }
+    if crate::reaction_cooldown::allow_reaction(&format!("hotkey:{action}")) {
+        let payload = HotkeyTriggeredPayload {
+            action,
+            accelerator,
+        };
+        if let Err(error) = app.emit("hotkey-triggered", payload) {
+            tracing::warn!("failed to emit hotkey-triggered: {error}");
+        }
+    }
+}
+
+pub fn restore_shortcuts(app: &AppHandle) {
+    let saved: HashMap<String, String> = load_value(app, SHORTCUTS_KEY).unwrap_or_default();
+    let bindings = app.state::<ShortcutBindings>();
+    let Ok(mut bindings) = bindings.bindings.lock() else {
+        return;
+    };
+
+    for (action, accelerator) in saved {
+        if !SUPPORTED_ACTIONS.contains(&action.as_str()) {
+            tracing::warn!("ignoring saved shortcut for unknown action {action}");
+            continue;
+        }
+        match register_accelerator(app, &accelerator) {
+            Ok(()) => {
+                bindings.insert(action, accelerator);
+            }
+            Err(error) => {
+                tracing::error!("failed to restore shortcut for {action}: {error}");
+                crate::record_backend_error(app, format!("restore shortcut failed: {error}"));
+            }
+        }
+    }
+}
+
+pub fn unregister_all_shortcuts(app: &AppHandle) {
+    if let Err(error) = app.global_shortcut().unregister_all() {
+        tracing::warn!("failed to unregister global shortcuts: {error}");
+    }
+    if let Ok(mut bindings) = app.state::<ShortcutBindings>().bindings.lock() {
+        bindings.clear();
+    }
+}
+
+#[tauri::command]
+pub fn get_shortcuts(state: State<'_, ShortcutBindings>) -> HashMap<String, String> {
+    state
+        .bindings
+        .lock()
+        .map(|bindings| bindings.clone())
+        .unwrap_or_default()
+}
+
+/// Binds `action` to `accelerator`. An empty accelerator clears the binding.
+#[tauri::command]
+pub fn set_shortcut(
+    app: AppHandle,
+    state: State<'_, ShortcutBindings>,
+    action: String,
+    accelerator: String,
+) -> Result<HashMap<String, String>, String> {
+    if !SUPPORTED_ACTIONS.contains(&action.as_str()) {
+        return Err(format!("unknown shortcut action: {action}"));
+    }
+
+    let accelerator = accelerator.trim().to_string();
+    let mut bindings = state
+        .bindings
+        .lock()
+        .map_err(|_| "shortcut state unavailable".to_string())?;
+
+    let previous = bindings.get(&action).cloned();
+    if previous.as_deref() == Some(accelerator.as_str()) {
+        return Ok(bindings.clone());
+    }
+
+    if !accelerator.is_empty() {
+        let shortcut = parse_accelerator(&accelerator)?;
+        if let Some((other, _)) = bindings.iter().find(|(other, bound)| {
+            *other != &action
+                && parse_accelerator(bound)
+                    .map(|candidate| candidate.id() == shortcut.id())
+                    .unwrap_or(false)
+        }) {
+            return Err(format!(
+                "shortcut conflict: {accelerator} is already bound to {other}"
+            ));
+        }
+    }
+
+    if let Some(previous) = previous.as_deref() {
+        unregister_accelerator(&app, previous);
+        bindings.remove(&action);
+    }
+
+    if !accelerator.is_empty() {
+        if let Err(error) = register_accelerator(&app, &accelerator) {
+            // Put the old binding back so a failed change doesn't leave the action unbound.
+            if let Some(previous) = previous {
+                if register_accelerator(&app, &previous).is_ok() {
+                    bindings.insert(action, previous);
+                }
+            }
+            return Err(error);
+        }
+        bindings.insert(action, accelerator);
+    }
+
+    save_value(&app, SHORTCUTS_KEY, &*bindings)?;
+    Ok(bindings.clone())
+}
@@ -0,0 +1,141 @@
+use crate::persist::STORE_FILE;
+use once_cell::sync::OnceCell;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+// Kept in the config dir rather than the store, since the store itself moves.
+const STORAGE_ROOT_FILE: &str = "storage-root.txt";
+// Takes precedence over the saved override, for machines where not even the
+// config dir is writable.
+const STORAGE_ROOT_ENV: &str = "STORAGE_ROOT";
+const LOGS_SUBDIR: &str = "logs";
+const DATA_SUBDIR: &str = "data";
+
+/// The override in effect for this run, resolved once at startup; changes
+/// apply after a restart.
+static STORAGE_ROOT: OnceCell<Option<PathBuf>> = OnceCell::new();
+/// Set when a saved override was ignored, so it can be logged once logging is up.
+static STORAGE_ROOT_FALLBACK: OnceCell<String> = OnceCell::new();
+
+fn override_file(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join(STORAGE_ROOT_FILE))
+        .map_err(|error| format!("failed to resolve app config dir: {error}"))
+}
+
+fn saved_override(app: &AppHandle) -> Option<PathBuf> {
+    let from_env = std::env::var_os(STORAGE_ROOT_ENV).filter(|value| !value.is_empty());
+    let saved = from_env.map(PathBuf::from).or_else(|| {
+        let raw = fs::read_to_string(override_file(app).ok()?).ok()?;
+        let trimmed = raw.trim();
+        (!trimmed.is_empty()).then(|| PathBuf::from(trimmed))
+    })?;
+
+    if saved.is_dir() {
+        Some(saved)
+    } else {
+        let _ = STORAGE_ROOT_FALLBACK.set(format!(
+            "storage root {} no longer exists; using the default directories",
+            saved.display()
+        ));
+        None
+    }
+}
+
+fn storage_root(app: &AppHandle) -> Option<&'static PathBuf> {
+    STORAGE_ROOT.get_or_init(|| saved_override(app)).as_ref()
+}
+
+/// Logs a saved override that had to be ignored. Call once logging is set up.
+pub(crate) fn report_fallback() {
+    if let Some(message) = STORAGE_ROOT_FALLBACK.get() {
+        tracing::warn!("{message}");
+    }
+}
+
+pub(crate) fn log_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match storage_root(app) {
+        Some(root) => Ok(root.join(LOGS_SUBDIR)),
+        None => app
+            .path()
+            .app_log_dir()
+            .map_err(|error| format!("failed to resolve app log dir: {error}")),
+    }
+}
+
+pub(crate) fn data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    match storage_root(app) {
+        Some(root) => Ok(root.join(DATA_SUBDIR)),
+        None => app
+            .path()
+            .app_data_dir()
+            .map_err(|error| format!("failed to resolve app data dir: {error}")),
+    }
+}
+
+/// What to pass to the store plugin: an absolute path under the override, or
+/// the bare file name, which the plugin resolves against the app data dir.
+pub(crate) fn store_path(app: &AppHandle) -> PathBuf {
+    match storage_root(app) {
+        Some(root) => root.join(DATA_SUBDIR).join(STORE_FILE),
+        None => PathBuf::from(STORE_FILE),
+    }
+}
+
+fn check_writable(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|error| format!("cannot create {}: {error}", dir.display()))?;
+    let probe = dir.join(".write-test");
+    fs::write(&probe, b"ok")
+        .map_err(|error| format!("{} is not writable: {error}", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Moves logs and the settings store under `path` (in `logs/` and `data/`)
+/// from the next launch on. A blank path goes back to the default
+/// directories. Existing files are not copied over.
+#[tauri::command]
+pub fn set_storage_root(app: AppHandle, path: String) -> Result<(), String> {
+    let override_file = override_file(&app)?;
+    let path = path.trim();
+    if path.is_empty() {
+        return match fs::remove_file(&override_file) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(format!(
+                "failed to remove {}: {error}",
+                override_file.display()
+            )),
+            _ => Ok(()),
+        };
+    }
+
+    let root = PathBuf::from(path);
+    if !root.is_absolute() {
+        return Err(format!("storage root must be an absolute path: {path}"));
+    }
+    check_writable(&root.join(LOGS_SUBDIR))?;
+    check_writable(&root.join(DATA_SUBDIR))?;
+
+    if let Some(parent) = override_file.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|error| format!("failed to create {}: {error}", parent.display()))?;
+    }
+    fs::write(&override_file, root.to_string_lossy().as_bytes()).map_err(|error| {
+        format!(
+            "failed to save the storage root to {} (set {STORAGE_ROOT_ENV} instead): {error}",
+            override_file.display()
+        )
+    })?;
+    tracing::info!(
+        "storage root set to {}; applies after restart",
+        root.display()
+    );
+    Ok(())
+}
+
+/// The store location the frontend should open, matching `store_path`.
+#[tauri::command]
+pub fn get_store_path(app: AppHandle) -> String {
+    store_path(&app).to_string_lossy().to_string()
+}
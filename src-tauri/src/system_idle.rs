@@ -0,0 +1,195 @@
+use crate::input_listener::SharedInputListenerState;
+use crate::UiState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const SYSTEM_IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+static SYSTEM_IDLE: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SystemIdlePayload {
+    idle: bool,
+    idle_ms: u64,
+}
+
+#[cfg(target_os = "windows")]
+fn system_idle_ms() -> Result<u64, String> {
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        dw_time: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+
+    let mut info = LastInputInfo {
+        cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+        dw_time: 0,
+    };
+    // SAFETY: `info` is a correctly sized LASTINPUTINFO that outlives the call.
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return Err("GetLastInputInfo failed".to_string());
+    }
+    // Both are 32-bit tick counts, so wrap-around after ~49 days cancels out.
+    // SAFETY: GetTickCount has no preconditions.
+    Ok(u64::from(
+        unsafe { GetTickCount() }.wrapping_sub(info.dw_time),
+    ))
+}
+
+#[cfg(target_os = "macos")]
+fn system_idle_ms() -> Result<u64, String> {
+    const K_CG_EVENT_SOURCE_STATE_COMBINED_SESSION_STATE: i32 = 0;
+    const K_CG_ANY_INPUT_EVENT_TYPE: u32 = u32::MAX;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    // SAFETY: a pure query on the session event source; no preconditions.
+    let seconds = unsafe {
+        CGEventSourceSecondsSinceLastEventType(
+            K_CG_EVENT_SOURCE_STATE_COMBINED_SESSION_STATE,
+            K_CG_ANY_INPUT_EVENT_TYPE,
+        )
+    };
+    if !seconds.is_finite() || seconds < 0.0 {
+        return Err("CGEventSourceSecondsSinceLastEventType returned no value".to_string());
+    }
+    Ok((seconds * 1000.0) as u64)
+}
+
+/// libX11 and libXss are loaded at runtime, so a system without the screen
+/// saver extension still starts and only loses system idle detection. Both
+/// libraries and the display connection are opened once and kept for the life
+/// of the process; the watcher polls every second.
+#[cfg(target_os = "linux")]
+struct X11Idle {
+    xlib: x11_dl::xlib::Xlib,
+    xss: x11_dl::xss::Xss,
+    display: *mut x11_dl::xlib::Display,
+    info: *mut x11_dl::xss::XScreenSaverInfo,
+}
+
+// SAFETY: the pointers are only used behind `X11_IDLE`'s mutex, so Xlib never
+// sees two threads on the connection at once.
+#[cfg(target_os = "linux")]
+unsafe impl Send for X11Idle {}
+
+#[cfg(target_os = "linux")]
+impl X11Idle {
+    fn open() -> Result<Self, String> {
+        use x11_dl::{xlib::Xlib, xss::Xss};
+
+        if std::env::var_os("DISPLAY").is_none() {
+            return Err("system idle time needs an X11 display".to_string());
+        }
+        let xlib = Xlib::open().map_err(|error| format!("failed to load libX11: {error}"))?;
+        let xss = Xss::open().map_err(|error| format!("failed to load libXss: {error}"))?;
+
+        // SAFETY: both pointers are checked for null; on failure the display is
+        // closed before returning.
+        unsafe {
+            let display = (xlib.XOpenDisplay)(std::ptr::null());
+            if display.is_null() {
+                return Err("failed to open the X11 display".to_string());
+            }
+            let info = (xss.XScreenSaverAllocInfo)();
+            if info.is_null() {
+                (xlib.XCloseDisplay)(display);
+                return Err("XScreenSaverAllocInfo failed".to_string());
+            }
+            Ok(Self {
+                xlib,
+                xss,
+                display,
+                info,
+            })
+        }
+    }
+
+    fn idle_ms(&mut self) -> Result<u64, String> {
+        // SAFETY: `display` and `info` are valid for as long as `self` lives.
+        unsafe {
+            let root = (self.xlib.XDefaultRootWindow)(self.display);
+            if (self.xss.XScreenSaverQueryInfo)(self.display, root, self.info) == 0 {
+                return Err("the X server has no screen saver extension".to_string());
+            }
+            Ok((*self.info).idle)
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+static X11_IDLE: once_cell::sync::Lazy<std::sync::Mutex<Result<X11Idle, String>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(X11Idle::open()));
+
+#[cfg(target_os = "linux")]
+fn system_idle_ms() -> Result<u64, String> {
+    let mut idle = X11_IDLE
+        .lock()
+        .map_err(|_| "X11 idle state unavailable".to_string())?;
+    idle.as_mut().map_err(|error| error.clone())?.idle_ms()
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn system_idle_ms() -> Result<u64, String> {
+    Err(format!(
+        "system idle time is not supported on {}",
+        std::env::consts::OS
+    ))
+}
+
+fn poll_system_idle(app: &AppHandle) {
+    let threshold_ms = app.state::<SharedInputListenerState>().idle_threshold_ms();
+
+    let idle_ms = match system_idle_ms() {
+        Ok(idle_ms) => idle_ms,
+        Err(error) => {
+            tracing::debug!("system idle poll failed: {error}");
+            return;
+        }
+    };
+    let idle = idle_ms >= threshold_ms;
+    if SYSTEM_IDLE.swap(idle, Ordering::SeqCst) != idle {
+        let _ = app.emit("system-idle", SystemIdlePayload { idle, idle_ms });
+    }
+}
+
+/// Emits `system-idle` on transitions across the idle threshold set with
+/// `set_idle_threshold_ms`, the same one `input-idle-changed` uses.
+pub(crate) fn spawn_system_idle_watcher(app: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("system-idle-watcher".to_string())
+        .spawn(move || loop {
+            if app.state::<UiState>().quitting.load(Ordering::SeqCst) {
+                break;
+            }
+            poll_system_idle(&app);
+            std::thread::sleep(SYSTEM_IDLE_POLL_INTERVAL);
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start system idle watcher: {error}");
+    }
+}
+
+/// Milliseconds since the last keyboard or mouse input anywhere on the system,
+/// as reported by the OS rather than by our own listener.
+#[tauri::command]
+pub fn get_system_idle_ms() -> Result<u64, String> {
+    system_idle_ms()
+}
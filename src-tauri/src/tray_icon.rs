@@ -0,0 +1,90 @@
+use crate::persist::{load_value, save_value};
+use crate::TRAY_ID;
+use std::fs;
+use std::io::Cursor;
+use std::path::Path;
+use tauri::image::Image;
+use tauri::AppHandle;
+
+const TRAY_ICON_PATH_KEY: &str = "trayIconPath";
+// Tray icons are tiny; a bigger file is almost certainly the wrong file.
+const MAX_TRAY_ICON_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Decodes a PNG, or the largest image in an ICO, into RGBA.
+fn load_tray_image(path: &Path) -> Result<Image<'static>, String> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    if !matches!(extension.as_deref(), Some("png" | "ico")) {
+        return Err(format!("{} is not a .png or .ico file", path.display()));
+    }
+
+    let metadata =
+        fs::metadata(path).map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    if metadata.len() > MAX_TRAY_ICON_BYTES {
+        return Err(format!(
+            "{} is larger than {} MB",
+            path.display(),
+            MAX_TRAY_ICON_BYTES / (1024 * 1024)
+        ));
+    }
+    let bytes =
+        fs::read(path).map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+
+    let decoded = if extension.as_deref() == Some("ico") {
+        let icon_dir = ico::IconDir::read(Cursor::new(&bytes))
+            .map_err(|error| format!("{} is not a valid ICO: {error}", path.display()))?;
+        icon_dir
+            .entries()
+            .iter()
+            .max_by_key(|entry| entry.width() * entry.height())
+            .ok_or_else(|| format!("{} contains no images", path.display()))?
+            .decode()
+    } else {
+        ico::IconImage::read_png(Cursor::new(&bytes))
+    }
+    .map_err(|error| format!("{} could not be decoded: {error}", path.display()))?;
+
+    let (width, height) = (decoded.width(), decoded.height());
+    Ok(Image::new_owned(decoded.into_rgba_data(), width, height))
+}
+
+/// Sets the tray icon, falling back to the app's window icon for `None`.
+fn apply_tray_icon(app: &AppHandle, icon: Option<Image<'static>>) -> Result<(), String> {
+    let tray = app
+        .tray_by_id(TRAY_ID)
+        .ok_or_else(|| "tray icon not found".to_string())?;
+    let icon = icon.or_else(|| app.default_window_icon().cloned());
+    tray.set_icon(icon).map_err(|error| error.to_string())
+}
+
+/// Reapplies the saved custom icon, or the default one when none is saved. A
+/// file that has since gone missing or broken leaves the current icon in place.
+pub(crate) fn restore_tray_icon(app: &AppHandle) {
+    let Some(path) = load_value::<Option<String>>(app, TRAY_ICON_PATH_KEY).flatten() else {
+        if let Err(error) = apply_tray_icon(app, None) {
+            tracing::warn!("failed to restore the default tray icon: {error}");
+        }
+        return;
+    };
+    let result =
+        load_tray_image(Path::new(&path)).and_then(|icon| apply_tray_icon(app, Some(icon)));
+    if let Err(error) = result {
+        tracing::warn!("keeping the default tray icon: {error}");
+    }
+}
+
+#[tauri::command]
+pub fn set_tray_icon(app: AppHandle, path: String) -> Result<(), String> {
+    let path = path.trim();
+    let icon = load_tray_image(Path::new(path))?;
+    apply_tray_icon(&app, Some(icon))?;
+    save_value(&app, TRAY_ICON_PATH_KEY, &path)
+}
+
+#[tauri::command]
+pub fn reset_tray_icon(app: AppHandle) -> Result<(), String> {
+    apply_tray_icon(&app, None)?;
+    save_value(&app, TRAY_ICON_PATH_KEY, &None::<String>)
+}
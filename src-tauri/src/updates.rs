@@ -0,0 +1,263 @@
+use crate::diagnostics::{now_timestamp_ms, SharedDiagnosticsState};
+use crate::persist::{load_value, save_value};
+use crate::UiState;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+const UPDATE_CHECK_INTERVAL_KEY: &str = "updateCheckIntervalHours";
+const LAST_UPDATE_CHECK_KEY: &str = "lastUpdateCheck";
+const MAX_UPDATE_CHECK_INTERVAL_HOURS: u64 = 24 * 30;
+// How often the scheduler wakes to see whether a check is due.
+const UPDATE_SCHEDULER_TICK: Duration = Duration::from_secs(60);
+const MS_PER_HOUR: u64 = 60 * 60 * 1_000;
+
+/// Hours between scheduled checks; 0 disables them.
+static UPDATE_CHECK_INTERVAL_HOURS: AtomicU64 = AtomicU64::new(0);
+static UPDATE_SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// The last package whose signature checked out, so installing right after
+/// `verify_pending_update` does not download it a second time.
+static VERIFIED_PACKAGE: Mutex<Option<VerifiedPackage>> = Mutex::new(None);
+
+struct VerifiedPackage {
+    version: String,
+    bytes: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVerification {
+    pub signature_valid: bool,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvailableUpdate {
+    pub version: String,
+    pub current_version: String,
+    pub date: Option<String>,
+    pub body: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateVerificationFailedPayload {
+    version: String,
+    reason: String,
+}
+
+async fn pending_update(app: &AppHandle) -> Result<Update, String> {
+    app.updater()
+        .map_err(|error| format!("updater unavailable: {error}"))?
+        .check()
+        .await
+        .map_err(|error| format!("failed to check for updates: {error}"))?
+        .ok_or_else(|| "no update is available".to_string())
+}
+
+fn is_signature_error(error: &tauri_plugin_updater::Error) -> bool {
+    matches!(
+        error,
+        tauri_plugin_updater::Error::Minisign(_)
+            | tauri_plugin_updater::Error::SignatureUtf8(_)
+            | tauri_plugin_updater::Error::Base64(_)
+    )
+}
+
+fn report_verification_failure(app: &AppHandle, version: &str, reason: String) {
+    tracing::error!("update {version} failed signature verification: {reason}");
+    app.state::<SharedDiagnosticsState>().record_error(
+        "error".to_string(),
+        format!("update {version} failed signature verification: {reason}"),
+        Some("updater".to_string()),
+    );
+    let payload = UpdateVerificationFailedPayload {
+        version: version.to_string(),
+        reason,
+    };
+    if let Err(error) = app.emit("update-verification-failed", payload) {
+        tracing::warn!("failed to emit update-verification-failed: {error}");
+    }
+}
+
+/// Downloads the package and checks it against the configured public key.
+/// `Ok(None)` means the signature did not verify; that has already been
+/// recorded and emitted.
+async fn download_verified(app: &AppHandle, update: &Update) -> Result<Option<Vec<u8>>, String> {
+    match update.download(|_, _| {}, || {}).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(error) if is_signature_error(&error) => {
+            report_verification_failure(app, &update.version, error.to_string());
+            Ok(None)
+        }
+        Err(error) => Err(format!(
+            "failed to download update {}: {error}",
+            update.version
+        )),
+    }
+}
+
+fn take_verified_package(version: &str) -> Option<Vec<u8>> {
+    let mut package = VERIFIED_PACKAGE.lock().ok()?;
+    package
+        .take_if(|package| package.version == version)
+        .map(|package| package.bytes)
+}
+
+/// Downloads the pending update and verifies its signature without installing.
+#[tauri::command]
+pub async fn verify_pending_update(app: AppHandle) -> Result<UpdateVerification, String> {
+    let update = pending_update(&app).await?;
+    let bytes = download_verified(&app, &update).await?;
+    let signature_valid = bytes.is_some();
+
+    if let (Some(bytes), Ok(mut package)) = (bytes, VERIFIED_PACKAGE.lock()) {
+        *package = Some(VerifiedPackage {
+            version: update.version.clone(),
+            bytes,
+        });
+    }
+    Ok(UpdateVerification {
+        signature_valid,
+        version: update.version,
+    })
+}
+
+/// Installs the pending update, refusing if its signature does not verify.
+/// Returns the installed version; it takes effect on the next launch.
+#[tauri::command]
+pub async fn install_pending_update(app: AppHandle) -> Result<String, String> {
+    let update = pending_update(&app).await?;
+    let bytes = match take_verified_package(&update.version) {
+        Some(bytes) => bytes,
+        None => download_verified(&app, &update).await?.ok_or_else(|| {
+            format!(
+                "refusing to install update {}: invalid signature",
+                update.version
+            )
+        })?,
+    };
+
+    update
+        .install(bytes)
+        .map_err(|error| format!("failed to install update {}: {error}", update.version))?;
+    Ok(update.version)
+}
+
+/// Checks the update endpoint, remembers when, and emits `update-available`
+/// when there is a newer version.
+async fn run_update_check(app: &AppHandle) -> Result<Option<AvailableUpdate>, String> {
+    let update = app
+        .updater()
+        .map_err(|error| format!("updater unavailable: {error}"))?
+        .check()
+        .await
+        .map_err(|error| format!("failed to check for updates: {error}"))?;
+    save_value(app, LAST_UPDATE_CHECK_KEY, &now_timestamp_ms())?;
+
+    let Some(update) = update else {
+        return Ok(None);
+    };
+    let available = AvailableUpdate {
+        version: update.version,
+        current_version: update.current_version,
+        date: update.date.map(|date| date.to_string()),
+        body: update.body,
+    };
+    if let Err(error) = app.emit("update-available", available.clone()) {
+        tracing::warn!("failed to emit update-available: {error}");
+    }
+    Ok(Some(available))
+}
+
+#[tauri::command]
+pub async fn check_for_update(app: AppHandle) -> Result<Option<AvailableUpdate>, String> {
+    run_update_check(&app).await
+}
+
+fn scheduled_check_due(app: &AppHandle) -> bool {
+    let hours = UPDATE_CHECK_INTERVAL_HOURS.load(Ordering::SeqCst);
+    if hours == 0 {
+        return false;
+    }
+    let last_checked: u64 = load_value(app, LAST_UPDATE_CHECK_KEY).unwrap_or(0);
+    now_timestamp_ms().saturating_sub(last_checked) >= hours * MS_PER_HOUR
+}
+
+/// Starts the one scheduler thread. It reads the interval on every tick, so
+/// changing it never needs a second thread.
+fn ensure_update_scheduler(app: &AppHandle) {
+    if UPDATE_SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("update-scheduler".to_string())
+        .spawn(move || loop {
+            if app.state::<UiState>().quitting.load(Ordering::SeqCst) {
+                break;
+            }
+            if scheduled_check_due(&app) {
+                if let Err(error) = tauri::async_runtime::block_on(run_update_check(&app)) {
+                    tracing::warn!("scheduled update check failed: {error}");
+                    app.state::<SharedDiagnosticsState>().record_error(
+                        "warn".to_string(),
+                        error,
+                        Some("updater".to_string()),
+                    );
+                    // Counts as a check so an offline machine retries next interval.
+                    let _ = save_value(&app, LAST_UPDATE_CHECK_KEY, &now_timestamp_ms());
+                }
+            }
+            std::thread::sleep(UPDATE_SCHEDULER_TICK);
+        });
+
+    if let Err(error) = spawned {
+        UPDATE_SCHEDULER_STARTED.store(false, Ordering::SeqCst);
+        tracing::error!("failed to start update scheduler: {error}");
+    }
+}
+
+/// Restores the saved interval at startup.
+pub(crate) fn restore_update_schedule(app: &AppHandle) {
+    let hours: Option<u64> = load_value::<Option<u64>>(app, UPDATE_CHECK_INTERVAL_KEY).flatten();
+    let hours = hours.unwrap_or(0).min(MAX_UPDATE_CHECK_INTERVAL_HOURS);
+    // Also stores 0 when the key is gone, so a reset stops scheduled checks.
+    UPDATE_CHECK_INTERVAL_HOURS.store(hours, Ordering::SeqCst);
+    if hours > 0 {
+        ensure_update_scheduler(app);
+    }
+}
+
+/// Checks for updates every `hours` in the background; `None` stops it.
+#[tauri::command]
+pub fn set_update_check_interval(
+    app: AppHandle,
+    hours: Option<u64>,
+) -> Result<Option<u64>, String> {
+    if let Some(hours) = hours {
+        if hours == 0 || hours > MAX_UPDATE_CHECK_INTERVAL_HOURS {
+            return Err(format!(
+                "update check interval must be between 1 and {MAX_UPDATE_CHECK_INTERVAL_HOURS} hours"
+            ));
+        }
+    }
+    save_value(&app, UPDATE_CHECK_INTERVAL_KEY, &hours)?;
+    UPDATE_CHECK_INTERVAL_HOURS.store(hours.unwrap_or(0), Ordering::SeqCst);
+    if hours.is_some() {
+        ensure_update_scheduler(&app);
+    }
+    Ok(hours)
+}
+
+/// When updates were last checked, scheduled or not, in Unix ms.
+#[tauri::command]
+pub fn get_last_update_check(app: AppHandle) -> Option<u64> {
+    load_value(&app, LAST_UPDATE_CHECK_KEY)
+}
@@ -0,0 +1,948 @@
+use crate::model_validate::CanvasSize;
+use crate::persist::{load_value, save_value};
+use crate::{main_window, UiState};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Monitor, PhysicalPosition, PhysicalSize, State};
+
+const LAST_ANCHOR_KEY: &str = "lastSnapAnchor";
+// Takes precedence over `LAST_ANCHOR_KEY` while set; `snap_to` clears it.
+const RELATIVE_ANCHOR_KEY: &str = "relativeAnchor";
+const DEFAULT_ANCHOR_MARGIN: i32 = 16;
+const SNAP_CONFIG_KEY: &str = "snapConfig";
+const MAX_SNAP_THRESHOLD_PX: u32 = 256;
+// Narrowest window a fitted model gets, so very tall canvases stay grabbable.
+const MIN_FITTED_WIDTH_PX: u32 = 120;
+
+const MONITOR_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+const MONITOR_SETTLE_POLL_INTERVAL: Duration = Duration::from_millis(250);
+// Docking reports several intermediate layouts; wait until they stop changing.
+const MONITOR_SETTLE_DURATION: Duration = Duration::from_millis(1500);
+
+pub const ANCHORS: [&str; 9] = [
+    "top-left",
+    "top",
+    "top-right",
+    "left",
+    "center",
+    "right",
+    "bottom-left",
+    "bottom",
+    "bottom-right",
+];
+
+// Interactive resizes fire `Resized` per frame; emit once they pause this long.
+const RESIZE_SETTLE_DURATION: Duration = Duration::from_millis(120);
+const RESIZE_POLL_INTERVAL: Duration = Duration::from_millis(40);
+
+static PENDING_RESIZE: Mutex<PendingResize> = Mutex::new(PendingResize {
+    size: None,
+    updated_at: None,
+    watching: false,
+});
+
+struct PendingResize {
+    size: Option<PhysicalSize<u32>>,
+    updated_at: Option<Instant>,
+    watching: bool,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WindowResizedPayload {
+    width: u32,
+    height: u32,
+    logical_width: f64,
+    logical_height: f64,
+    scale_factor: f64,
+}
+
+// Toggling click-through makes focus flicker; only a settled change is reported.
+const DEFAULT_FOCUS_DEBOUNCE_MS: u64 = 150;
+const MAX_FOCUS_DEBOUNCE_MS: u64 = 2_000;
+const FOCUS_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+static FOCUS_DEBOUNCE_MS: AtomicU64 = AtomicU64::new(DEFAULT_FOCUS_DEBOUNCE_MS);
+static PENDING_FOCUS: Mutex<PendingFocus> = Mutex::new(PendingFocus {
+    focused: None,
+    updated_at: None,
+    watching: false,
+    last_emitted: None,
+});
+
+struct PendingFocus {
+    focused: Option<bool>,
+    updated_at: Option<Instant>,
+    watching: bool,
+    /// A flicker that settles back on this value emits nothing.
+    last_emitted: Option<bool>,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+struct FocusChangedPayload {
+    focused: bool,
+}
+
+// Hidden windows report no geometry on some platforms; serve the last good read.
+static LAST_WINDOW_GEOMETRY: Mutex<Option<WindowGeometry>> = Mutex::new(None);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    /// Index into the monitor list, or `None` when the window is off every monitor.
+    pub monitor_index: Option<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub work_area_x: i32,
+    pub work_area_y: i32,
+    pub work_area_width: u32,
+    pub work_area_height: u32,
+    pub scale_factor: f64,
+    pub primary: bool,
+}
+
+impl MonitorInfo {
+    fn from_monitor(monitor: &Monitor, primary: Option<&Monitor>) -> Self {
+        let work_area = monitor.work_area();
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+            work_area_x: work_area.position.x,
+            work_area_y: work_area.position.y,
+            work_area_width: work_area.size.width,
+            work_area_height: work_area.size.height,
+            scale_factor: monitor.scale_factor(),
+            primary: primary.is_some_and(|primary| {
+                primary.name() == monitor.name() && primary.position() == monitor.position()
+            }),
+        }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && x < self.x + self.width as i32
+            && y < self.y + self.height as i32
+    }
+}
+
+/// Edge-snapping behaviour applied by the pet window while it is dragged.
+/// `enabled` mirrors `UiState::snap_enabled`; the rest is persisted here.
+#[derive(Clone, Debug, Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SnapConfig {
+    pub enabled: bool,
+    pub threshold_px: u32,
+    pub snap_to_monitor_edges: bool,
+    pub snap_to_corners: bool,
+    /// Hold off while the window is being dragged and snap once on release,
+    /// instead of pulling the window to edges mid-drag.
+    pub snap_on_release: bool,
+}
+
+impl Default for SnapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_px: 16,
+            snap_to_monitor_edges: true,
+            snap_to_corners: true,
+            snap_on_release: true,
+        }
+    }
+}
+
+/// Mirrors `snapPositionToEdges` in `src/lib/windowInteraction.ts`.
+fn snap_position(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    monitor: &MonitorInfo,
+    config: &SnapConfig,
+) -> PhysicalPosition<i32> {
+    let threshold = config.threshold_px as i32;
+    let left = monitor.work_area_x;
+    let top = monitor.work_area_y;
+    let right = left + monitor.work_area_width as i32 - size.width as i32;
+    let bottom = top + monitor.work_area_height as i32 - size.height as i32;
+    let nearest_edge = |value: i32, low: i32, high: i32| {
+        if (value - low).abs() < threshold {
+            Some(low)
+        } else if (high - value).abs() < threshold {
+            Some(high)
+        } else {
+            None
+        }
+    };
+
+    let edge_x = nearest_edge(position.x, left, right);
+    let edge_y = nearest_edge(position.y, top, bottom);
+    match (edge_x, edge_y) {
+        (Some(x), Some(y)) if config.snap_to_corners => PhysicalPosition::new(x, y),
+        (Some(x), Some(y)) if config.snap_to_monitor_edges => {
+            // Corners disabled: stick to whichever edge is closer.
+            if (x - position.x).abs() <= (y - position.y).abs() {
+                PhysicalPosition::new(x, position.y)
+            } else {
+                PhysicalPosition::new(position.x, y)
+            }
+        }
+        (Some(_), Some(_)) => position,
+        _ if config.snap_to_monitor_edges => {
+            PhysicalPosition::new(edge_x.unwrap_or(position.x), edge_y.unwrap_or(position.y))
+        }
+        _ => position,
+    }
+}
+
+/// The single snap after a window drag ends, when `snap_on_release` is set.
+pub(crate) fn snap_after_drag(app: &AppHandle) -> Result<(), String> {
+    let state = app.state::<UiState>();
+    let config = get_snap_config(app.clone(), state);
+    if !config.enabled || !config.snap_on_release {
+        return Ok(());
+    }
+
+    let window = main_window(app, "drag-end")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let center_x = position.x + size.width as i32 / 2;
+    let center_y = position.y + size.height as i32 / 2;
+    let monitors = list_monitors(app)?;
+    let Some(monitor) = monitors
+        .iter()
+        .find(|monitor| monitor.contains(center_x, center_y))
+    else {
+        return Ok(());
+    };
+
+    let snapped = snap_position(position, size, monitor, &config);
+    if snapped != position {
+        window
+            .set_position(snapped)
+            .map_err(|error| error.to_string())?;
+        emit_window_moved(app, snapped);
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct WindowMovedPayload {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedAnchor {
+    anchor: String,
+    margin: i32,
+}
+
+/// A position as fractions of a monitor's work area: 0 puts the window flush
+/// with the left/top edge, 1 flush with the right/bottom edge.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelativeAnchor {
+    pub monitor_index: usize,
+    pub frac_x: f64,
+    pub frac_y: f64,
+}
+
+pub(crate) fn emit_window_moved(app: &AppHandle, position: PhysicalPosition<i32>) {
+    let payload = WindowMovedPayload {
+        x: position.x,
+        y: position.y,
+    };
+    let _ = app.emit("window-moved", payload);
+}
+
+/// Returns the window's top-left corner for `anchor` inside the monitor work area.
+fn anchor_position(
+    anchor: &str,
+    area_position: PhysicalPosition<i32>,
+    area_size: (i32, i32),
+    window_size: (i32, i32),
+    margin: i32,
+) -> Option<PhysicalPosition<i32>> {
+    let (area_width, area_height) = area_size;
+    let (window_width, window_height) = window_size;
+
+    let left = area_position.x + margin;
+    let right = area_position.x + area_width - window_width - margin;
+    let center_x = area_position.x + (area_width - window_width) / 2;
+    let top = area_position.y + margin;
+    let bottom = area_position.y + area_height - window_height - margin;
+    let center_y = area_position.y + (area_height - window_height) / 2;
+
+    let (x, y) = match anchor {
+        "top-left" => (left, top),
+        "top" => (center_x, top),
+        "top-right" => (right, top),
+        "left" => (left, center_y),
+        "center" => (center_x, center_y),
+        "right" => (right, center_y),
+        "bottom-left" => (left, bottom),
+        "bottom" => (center_x, bottom),
+        "bottom-right" => (right, bottom),
+        _ => return None,
+    };
+    Some(PhysicalPosition::new(x, y))
+}
+
+fn snap_to_internal(app: &AppHandle, anchor: &str, margin: i32) -> Result<(), String> {
+    if !ANCHORS.contains(&anchor) {
+        return Err(format!(
+            "unknown anchor {anchor}; expected one of {}",
+            ANCHORS.join(", ")
+        ));
+    }
+
+    let window = main_window(app, "snap_to")?;
+    let monitor = window
+        .current_monitor()
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| "main window is not on any monitor".to_string())?;
+    let window_size = window.outer_size().map_err(|error| error.to_string())?;
+    let work_area = monitor.work_area();
+
+    let position = anchor_position(
+        anchor,
+        work_area.position,
+        (work_area.size.width as i32, work_area.size.height as i32),
+        (window_size.width as i32, window_size.height as i32),
+        margin,
+    )
+    .ok_or_else(|| format!("unknown anchor {anchor}"))?;
+
+    window
+        .set_position(position)
+        .map_err(|error| error.to_string())
+}
+
+pub(crate) fn list_monitors(app: &AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    let primary = app.primary_monitor().map_err(|error| error.to_string())?;
+    let monitors = app
+        .available_monitors()
+        .map_err(|error| error.to_string())?;
+    Ok(monitors
+        .iter()
+        .map(|monitor| MonitorInfo::from_monitor(monitor, primary.as_ref()))
+        .collect())
+}
+
+/// Moves the main window fully inside the work area of the monitor under its
+/// centre, or the primary monitor when it ended up on a display that went away.
+pub(crate) fn clamp_main_window_on_screen(
+    app: &AppHandle,
+    monitors: &[MonitorInfo],
+) -> Result<(), String> {
+    let window = main_window(app, "monitors-changed")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let (width, height) = (size.width as i32, size.height as i32);
+
+    let center_x = position.x + width / 2;
+    let center_y = position.y + height / 2;
+    let Some(target) = monitors
+        .iter()
+        .find(|monitor| monitor.contains(center_x, center_y))
+        .or_else(|| monitors.iter().find(|monitor| monitor.primary))
+        .or_else(|| monitors.first())
+    else {
+        return Ok(());
+    };
+
+    let max_x = target.work_area_x + (target.work_area_width as i32 - width).max(0);
+    let max_y = target.work_area_y + (target.work_area_height as i32 - height).max(0);
+    let clamped = PhysicalPosition::new(
+        position.x.clamp(target.work_area_x, max_x),
+        position.y.clamp(target.work_area_y, max_y),
+    );
+    if clamped != position {
+        window
+            .set_position(clamped)
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(())
+}
+
+fn handle_monitors_changed(app: &AppHandle, monitors: &[MonitorInfo]) {
+    tracing::info!(
+        "display configuration changed: {} monitor(s)",
+        monitors.len()
+    );
+    if let Err(error) = clamp_main_window_on_screen(app, monitors) {
+        tracing::warn!("failed to clamp window after display change: {error}");
+    }
+    reapply_last_anchor(app);
+    let _ = app.emit("monitors-changed", monitors);
+}
+
+/// Watches the display layout and emits one `monitors-changed` per settled change.
+/// Tauri has no portable hotplug event, so the layout is sampled instead.
+pub(crate) fn spawn_monitor_watcher(app: AppHandle) {
+    let spawned = std::thread::Builder::new()
+        .name("monitor-watcher".to_string())
+        .spawn(move || {
+            let mut last = list_monitors(&app).unwrap_or_default();
+            loop {
+                std::thread::sleep(MONITOR_POLL_INTERVAL);
+                if app.state::<UiState>().quitting.load(Ordering::SeqCst) {
+                    break;
+                }
+
+                let Ok(mut current) = list_monitors(&app) else {
+                    continue;
+                };
+                if current == last {
+                    continue;
+                }
+
+                let mut stable_since = Instant::now();
+                while stable_since.elapsed() < MONITOR_SETTLE_DURATION {
+                    std::thread::sleep(MONITOR_SETTLE_POLL_INTERVAL);
+                    if let Ok(next) = list_monitors(&app) {
+                        if next != current {
+                            current = next;
+                            stable_since = Instant::now();
+                        }
+                    }
+                }
+
+                // An undock/redock burst can land back on the original layout.
+                if current != last {
+                    handle_monitors_changed(&app, &current);
+                    last = current;
+                }
+            }
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start monitor watcher: {error}");
+    }
+}
+
+fn read_window_geometry(app: &AppHandle) -> Result<WindowGeometry, String> {
+    let window = main_window(app, "get_window_geometry")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    if size.width == 0 || size.height == 0 {
+        return Err("main window reports no size".to_string());
+    }
+    let scale_factor = window.scale_factor().map_err(|error| error.to_string())?;
+
+    let center_x = position.x + size.width as i32 / 2;
+    let center_y = position.y + size.height as i32 / 2;
+    let monitor_index = list_monitors(app)?
+        .iter()
+        .position(|monitor| monitor.contains(center_x, center_y));
+
+    Ok(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        scale_factor,
+        monitor_index,
+    })
+}
+
+fn emit_window_resized(app: &AppHandle, size: PhysicalSize<u32>) {
+    let scale_factor = main_window(app, "window-resized")
+        .and_then(|window| window.scale_factor().map_err(|error| error.to_string()))
+        .unwrap_or(1.0);
+    let logical = size.to_logical::<f64>(scale_factor);
+    let _ = app.emit(
+        "window-resized",
+        WindowResizedPayload {
+            width: size.width,
+            height: size.height,
+            logical_width: logical.width,
+            logical_height: logical.height,
+            scale_factor,
+        },
+    );
+}
+
+/// Records a main-window resize; called from `WindowEvent::Resized`. The event
+/// is emitted by a short-lived watcher once the size stops changing.
+pub(crate) fn record_window_resized(app: &AppHandle, size: PhysicalSize<u32>) {
+    let Ok(mut pending) = PENDING_RESIZE.lock() else {
+        return;
+    };
+    pending.size = Some(size);
+    pending.updated_at = Some(Instant::now());
+    if pending.watching {
+        return;
+    }
+    pending.watching = true;
+    drop(pending);
+
+    let watcher_app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("resize-debounce".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(RESIZE_POLL_INTERVAL);
+            let size = {
+                let Ok(mut pending) = PENDING_RESIZE.lock() else {
+                    return;
+                };
+                if pending
+                    .updated_at
+                    .is_some_and(|at| at.elapsed() < RESIZE_SETTLE_DURATION)
+                {
+                    continue;
+                }
+                pending.watching = false;
+                pending.size.take()
+            };
+            if let Some(size) = size {
+                emit_window_resized(&watcher_app, size);
+            }
+            return;
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start resize debounce: {error}");
+        if let Ok(mut pending) = PENDING_RESIZE.lock() {
+            pending.watching = false;
+        }
+    }
+}
+
+/// Called from `WindowEvent::Focused`; emits one `focus-changed` once focus has
+/// stayed put for the debounce interval.
+pub(crate) fn record_focus_changed(app: &AppHandle, focused: bool) {
+    let Ok(mut pending) = PENDING_FOCUS.lock() else {
+        return;
+    };
+    pending.focused = Some(focused);
+    pending.updated_at = Some(Instant::now());
+    if pending.watching {
+        return;
+    }
+    pending.watching = true;
+    drop(pending);
+
+    let watcher_app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("focus-debounce".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(FOCUS_POLL_INTERVAL);
+            let settle = Duration::from_millis(FOCUS_DEBOUNCE_MS.load(Ordering::Relaxed));
+            let changed = {
+                let Ok(mut pending) = PENDING_FOCUS.lock() else {
+                    return;
+                };
+                if pending.updated_at.is_some_and(|at| at.elapsed() < settle) {
+                    continue;
+                }
+                pending.watching = false;
+                let focused = pending.focused.take();
+                let changed = focused.filter(|focused| pending.last_emitted != Some(*focused));
+                if changed.is_some() {
+                    pending.last_emitted = changed;
+                }
+                changed
+            };
+            if let Some(focused) = changed {
+                let _ = watcher_app.emit("focus-changed", FocusChangedPayload { focused });
+            }
+            return;
+        });
+
+    if let Err(error) = spawned {
+        tracing::error!("failed to start focus debounce: {error}");
+        if let Ok(mut pending) = PENDING_FOCUS.lock() {
+            pending.watching = false;
+        }
+    }
+}
+
+/// Places the window at `anchor`. If that monitor is gone (e.g. after
+/// undocking) the primary one is used until it comes back.
+fn apply_relative_anchor(app: &AppHandle, anchor: &RelativeAnchor) -> Result<(), String> {
+    let window = main_window(app, "relative_anchor")?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let monitors = list_monitors(app)?;
+    let monitor = monitors
+        .get(anchor.monitor_index)
+        .or_else(|| monitors.iter().find(|monitor| monitor.primary))
+        .or_else(|| monitors.first())
+        .ok_or_else(|| "no monitors available".to_string())?;
+
+    let free_width = (monitor.work_area_width as i32 - size.width as i32).max(0);
+    let free_height = (monitor.work_area_height as i32 - size.height as i32).max(0);
+    let position = PhysicalPosition::new(
+        monitor.work_area_x + (f64::from(free_width) * anchor.frac_x).round() as i32,
+        monitor.work_area_y + (f64::from(free_height) * anchor.frac_y).round() as i32,
+    );
+    window
+        .set_position(position)
+        .map_err(|error| error.to_string())?;
+    emit_window_moved(app, position);
+    Ok(())
+}
+
+/// Re-docks to the last explicit anchor, e.g. after the resolution changed.
+pub(crate) fn reapply_last_anchor(app: &AppHandle) {
+    if app.state::<UiState>().locked.load(Ordering::SeqCst) {
+        return;
+    }
+    if let Some(relative) = load_value::<Option<RelativeAnchor>>(app, RELATIVE_ANCHOR_KEY).flatten()
+    {
+        if let Err(error) = apply_relative_anchor(app, &relative) {
+            tracing::warn!("failed to reapply relative anchor: {error}");
+        }
+        return;
+    }
+    let Some(saved) = load_value::<SavedAnchor>(app, LAST_ANCHOR_KEY) else {
+        return;
+    };
+    if let Err(error) = snap_to_internal(app, &saved.anchor, saved.margin) {
+        tracing::warn!("failed to reapply anchor {}: {error}", saved.anchor);
+    }
+}
+
+#[tauri::command]
+pub fn snap_to(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    anchor: String,
+    margin: Option<i32>,
+) -> Result<(), String> {
+    if state.locked.load(Ordering::SeqCst) {
+        return Err("window is locked; unlock it before moving the pet".to_string());
+    }
+
+    let margin = margin.unwrap_or(DEFAULT_ANCHOR_MARGIN).max(0);
+    snap_to_internal(&app, &anchor, margin)?;
+    save_value(&app, RELATIVE_ANCHOR_KEY, &None::<RelativeAnchor>)?;
+    save_value(&app, LAST_ANCHOR_KEY, &SavedAnchor { anchor, margin })
+}
+
+/// Pins the pet to a fractional position on monitor `monitor_index` (as listed
+/// by `get_monitors`) and keeps it there across resolution and layout changes.
+/// Fractions are clamped to `[0, 1]`.
+#[tauri::command]
+pub fn set_relative_anchor(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    monitor_index: usize,
+    frac_x: f64,
+    frac_y: f64,
+) -> Result<RelativeAnchor, String> {
+    if state.locked.load(Ordering::SeqCst) {
+        return Err("window is locked; unlock it before moving the pet".to_string());
+    }
+    if !frac_x.is_finite() || !frac_y.is_finite() {
+        return Err("anchor fractions must be finite numbers".to_string());
+    }
+    let monitor_count = list_monitors(&app)?.len();
+    if monitor_index >= monitor_count {
+        return Err(format!(
+            "monitor index {monitor_index} is out of range; {monitor_count} monitor(s) available"
+        ));
+    }
+
+    let anchor = RelativeAnchor {
+        monitor_index,
+        frac_x: frac_x.clamp(0.0, 1.0),
+        frac_y: frac_y.clamp(0.0, 1.0),
+    };
+    apply_relative_anchor(&app, &anchor)?;
+    save_value(&app, RELATIVE_ANCHOR_KEY, &Some(anchor))?;
+    Ok(anchor)
+}
+
+/// Moves the pet by a pixel delta (physical pixels), then pulls it back inside
+/// the work area if the step pushed it off screen.
+#[tauri::command]
+pub fn nudge_pet(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    dx: i32,
+    dy: i32,
+) -> Result<(), String> {
+    if state.locked.load(Ordering::SeqCst) {
+        return Err("window is locked; unlock it before moving the pet".to_string());
+    }
+
+    let window = main_window(&app, "nudge_pet")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(
+            position.x.saturating_add(dx),
+            position.y.saturating_add(dy),
+        ))
+        .map_err(|error| error.to_string())?;
+    clamp_main_window_on_screen(&app, &list_monitors(&app)?)?;
+
+    let moved = window.outer_position().map_err(|error| error.to_string())?;
+    emit_window_moved(&app, moved);
+    Ok(())
+}
+
+/// Recovery action: puts the pet in the middle of the work area of the monitor
+/// it is on (the primary one when it is off screen) and brings it to the front.
+/// Unlike `snap_to` this ignores the lock, since it is meant for a lost pet.
+#[tauri::command]
+pub fn center_pet(app: AppHandle) -> Result<(), String> {
+    let window = main_window(&app, "center_pet")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let (width, height) = (size.width as i32, size.height as i32);
+
+    let monitors = list_monitors(&app)?;
+    let target = monitors
+        .iter()
+        .find(|monitor| monitor.contains(position.x + width / 2, position.y + height / 2))
+        .or_else(|| monitors.iter().find(|monitor| monitor.primary))
+        .or_else(|| monitors.first())
+        .ok_or_else(|| "no monitors available".to_string())?;
+
+    let centered = anchor_position(
+        "center",
+        PhysicalPosition::new(target.work_area_x, target.work_area_y),
+        (
+            target.work_area_width as i32,
+            target.work_area_height as i32,
+        ),
+        (width, height),
+        0,
+    )
+    .ok_or_else(|| "failed to compute the centre position".to_string())?;
+    window
+        .set_position(centered)
+        .map_err(|error| error.to_string())?;
+
+    if !window.is_visible().map_err(|error| error.to_string())? {
+        window.show().map_err(|error| error.to_string())?;
+        crate::reapply_window_flags_logged(&app, "center_pet");
+    }
+    let _ = window.set_focus();
+    Ok(())
+}
+
+/// Gives the main window the model canvas's aspect ratio, keeping its height
+/// and staying within the work area of the monitor it is on.
+pub(crate) fn fit_main_window_to_canvas(
+    app: &AppHandle,
+    canvas: &CanvasSize,
+) -> Result<(), String> {
+    let window = main_window(app, "fit_main_window_to_canvas")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+
+    let monitors = list_monitors(app)?;
+    let center_x = position.x + size.width as i32 / 2;
+    let center_y = position.y + size.height as i32 / 2;
+    let (max_width, max_height) = monitors
+        .iter()
+        .find(|monitor| monitor.contains(center_x, center_y))
+        .or_else(|| monitors.iter().find(|monitor| monitor.primary))
+        .map_or((u32::MAX, u32::MAX), |monitor| {
+            (monitor.work_area_width, monitor.work_area_height)
+        });
+
+    let height = size.height.min(max_height);
+    let width = (f64::from(height) * canvas.width / canvas.height).round() as u32;
+    let width = width.clamp(MIN_FITTED_WIDTH_PX.min(max_width), max_width);
+    if (width, height) == (size.width, size.height) {
+        return Ok(());
+    }
+    window
+        .set_size(PhysicalSize::new(width, height))
+        .map_err(|error| error.to_string())?;
+    clamp_main_window_on_screen(app, &monitors)
+}
+
+/// How long focus must stay unchanged before `focus-changed` fires; clamped to 2s.
+#[tauri::command]
+pub fn set_focus_debounce_ms(ms: u64) -> u64 {
+    let ms = ms.min(MAX_FOCUS_DEBOUNCE_MS);
+    FOCUS_DEBOUNCE_MS.store(ms, Ordering::SeqCst);
+    ms
+}
+
+#[tauri::command]
+pub fn get_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    list_monitors(&app)
+}
+
+/// Refreshes the geometry `get_window_geometry` falls back on; called at startup
+/// and from `WindowEvent::Moved`/`Resized`, so a window hidden before the first
+/// query still has one. A failed read keeps the previous value.
+pub(crate) fn remember_window_geometry(app: &AppHandle) {
+    let Ok(geometry) = read_window_geometry(app) else {
+        return;
+    };
+    if let Ok(mut last) = LAST_WINDOW_GEOMETRY.lock() {
+        *last = Some(geometry);
+    }
+}
+
+#[tauri::command]
+pub fn get_window_geometry(app: AppHandle) -> Result<WindowGeometry, String> {
+    let mut last = LAST_WINDOW_GEOMETRY
+        .lock()
+        .map_err(|_| "window geometry state unavailable".to_string())?;
+
+    match read_window_geometry(&app) {
+        Ok(geometry) => {
+            *last = Some(geometry.clone());
+            Ok(geometry)
+        }
+        Err(error) => last
+            .clone()
+            .ok_or_else(|| format!("window geometry unavailable: {error}")),
+    }
+}
+
+#[tauri::command]
+pub fn get_snap_config(app: AppHandle, state: State<'_, UiState>) -> SnapConfig {
+    let mut config: SnapConfig = load_value(&app, SNAP_CONFIG_KEY).unwrap_or_default();
+    config.enabled = state.snap_enabled.load(Ordering::SeqCst);
+    config
+}
+
+/// Replaces the whole snap configuration. `enabled` goes through the same path
+/// as `set_snap_enabled`, so both commands stay in sync.
+#[tauri::command]
+pub fn set_snap_config(
+    app: AppHandle,
+    state: State<'_, UiState>,
+    config: SnapConfig,
+) -> Result<SnapConfig, String> {
+    let config = SnapConfig {
+        threshold_px: config.threshold_px.min(MAX_SNAP_THRESHOLD_PX),
+        ..config
+    };
+    save_value(&app, SNAP_CONFIG_KEY, &config)?;
+    crate::set_snap_internal(&app, &state, config.enabled)?;
+    let _ = app.emit("snap-config-changed", &config);
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 1920x1040 work area (taskbar at the bottom) on a monitor left of the
+    // primary one, so coordinates are negative like on real setups.
+    fn monitor() -> MonitorInfo {
+        MonitorInfo {
+            name: None,
+            x: -1920,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            work_area_x: -1920,
+            work_area_y: 0,
+            work_area_width: 1920,
+            work_area_height: 1040,
+            scale_factor: 1.0,
+            primary: false,
+        }
+    }
+
+    const WINDOW: PhysicalSize<u32> = PhysicalSize {
+        width: 200,
+        height: 300,
+    };
+
+    fn snap(x: i32, y: i32, config: &SnapConfig) -> (i32, i32) {
+        let snapped = snap_position(PhysicalPosition::new(x, y), WINDOW, &monitor(), config);
+        (snapped.x, snapped.y)
+    }
+
+    #[test]
+    fn snaps_to_an_edge_inside_the_threshold() {
+        let config = SnapConfig::default();
+
+        assert_eq!(snap(-1910, 400, &config), (-1920, 400));
+        // Right and bottom edges account for the window size.
+        assert_eq!(snap(-210, 400, &config), (-200, 400));
+        assert_eq!(snap(-1000, 735, &config), (-1000, 740));
+    }
+
+    #[test]
+    fn the_threshold_itself_does_not_snap() {
+        let config = SnapConfig::default();
+
+        assert_eq!(snap(-1904, 400, &config), (-1904, 400));
+        assert_eq!(snap(-1905, 400, &config), (-1920, 400));
+        assert_eq!(
+            snap(
+                -1910,
+                400,
+                &SnapConfig {
+                    threshold_px: 0,
+                    ..SnapConfig::default()
+                }
+            ),
+            (-1910, 400)
+        );
+    }
+
+    #[test]
+    fn corners_snap_both_axes() {
+        let config = SnapConfig::default();
+
+        assert_eq!(snap(-1910, 5, &config), (-1920, 0));
+        assert_eq!(snap(-205, 730, &config), (-200, 740));
+    }
+
+    #[test]
+    fn without_corners_only_the_closer_edge_snaps() {
+        let config = SnapConfig {
+            snap_to_corners: false,
+            ..SnapConfig::default()
+        };
+
+        assert_eq!(snap(-1910, 3, &config), (-1910, 0));
+        assert_eq!(snap(-1918, 10, &config), (-1920, 10));
+        // A tie goes to the horizontal edge.
+        assert_eq!(snap(-1915, 5, &config), (-1920, 5));
+    }
+
+    #[test]
+    fn without_edges_only_corners_snap() {
+        let config = SnapConfig {
+            snap_to_monitor_edges: false,
+            ..SnapConfig::default()
+        };
+
+        assert_eq!(snap(-1910, 400, &config), (-1910, 400));
+        assert_eq!(snap(-1910, 5, &config), (-1920, 0));
+
+        let neither = SnapConfig {
+            snap_to_corners: false,
+            ..config
+        };
+        assert_eq!(snap(-1910, 5, &neither), (-1910, 5));
+    }
+
+    #[test]
+    fn far_from_every_edge_nothing_moves() {
+        assert_eq!(snap(-1000, 400, &SnapConfig::default()), (-1000, 400));
+    }
+}
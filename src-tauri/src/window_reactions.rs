@@ -0,0 +1,160 @@
+use crate::active_window::ActiveWindow;
+use crate::model_scan::glob_match;
+use crate::motions::random_motion;
+use crate::persist::{load_value, save_value};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Listener, Manager};
+
+const WINDOW_REACTION_RULES_KEY: &str = "windowReactionRules";
+
+/// Plays a random motion from `motion_group` when the focused window matches.
+/// Each pattern is a case-insensitive substring, or a glob when it contains
+/// `*` or `?`; every pattern that is set must match.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowReactionRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_pattern: Option<String>,
+    pub motion_group: String,
+}
+
+fn pattern_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    if pattern.contains(['*', '?']) {
+        glob_match(pattern.as_bytes(), text.as_bytes())
+    } else {
+        text.contains(&pattern)
+    }
+}
+
+impl WindowReactionRule {
+    fn matches(&self, window: &ActiveWindow) -> bool {
+        let app = self.app_pattern.as_deref();
+        let title = self.title_pattern.as_deref();
+        (app.is_some() || title.is_some())
+            && app.is_none_or(|pattern| pattern_matches(pattern, &window.app_name))
+            && title.is_none_or(|pattern| pattern_matches(pattern, &window.title))
+    }
+}
+
+fn load_rules(app: &AppHandle) -> Vec<WindowReactionRule> {
+    load_value(app, WINDOW_REACTION_RULES_KEY).unwrap_or_default()
+}
+
+fn handle_active_window_changed(app: &AppHandle, payload: &str) {
+    // Do-not-disturb keeps the pet inert, reactions included.
+    if app
+        .state::<crate::UiState>()
+        .do_not_disturb
+        .load(Ordering::SeqCst)
+    {
+        return;
+    }
+    let window: ActiveWindow = match serde_json::from_str(payload) {
+        Ok(window) => window,
+        Err(error) => {
+            tracing::debug!("ignoring malformed active-window-changed payload: {error}");
+            return;
+        }
+    };
+
+    let Some(rule) = load_rules(app)
+        .into_iter()
+        .find(|rule| rule.matches(&window))
+    else {
+        return;
+    };
+    match random_motion(app.clone(), rule.motion_group.clone()) {
+        Ok(Some(_)) => {}
+        Ok(None) => tracing::debug!(
+            "window reaction for {} held back by the cooldown",
+            window.app_name
+        ),
+        Err(error) => tracing::warn!(
+            "window reaction for {} failed to play {}: {error}",
+            window.app_name,
+            rule.motion_group
+        ),
+    }
+}
+
+/// Evaluates the rules on every `active-window-changed` (emitted by the
+/// `active_window` watcher), so reactions work without the pet window having
+/// focus.
+pub(crate) fn listen_for_active_window(app: &AppHandle) {
+    let handler_app = app.clone();
+    app.listen_any("active-window-changed", move |event| {
+        handle_active_window_changed(&handler_app, event.payload());
+    });
+}
+
+#[tauri::command]
+pub fn get_window_reaction_rules(app: AppHandle) -> Vec<WindowReactionRule> {
+    load_rules(&app)
+}
+
+/// Replaces the rule list. Rules are tried in order and the first match wins.
+#[tauri::command]
+pub fn set_window_reaction_rules(
+    app: AppHandle,
+    rules: Vec<WindowReactionRule>,
+) -> Result<usize, String> {
+    for (index, rule) in rules.iter().enumerate() {
+        if rule.motion_group.trim().is_empty() {
+            return Err(format!("rule {} has no motion group", index + 1));
+        }
+        if rule.app_pattern.is_none() && rule.title_pattern.is_none() {
+            return Err(format!("rule {} needs an app or title pattern", index + 1));
+        }
+    }
+    save_value(&app, WINDOW_REACTION_RULES_KEY, &rules)?;
+    Ok(rules.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(app_pattern: Option<&str>, title_pattern: Option<&str>) -> WindowReactionRule {
+        WindowReactionRule {
+            app_pattern: app_pattern.map(str::to_string),
+            title_pattern: title_pattern.map(str::to_string),
+            motion_group: "Curious".to_string(),
+        }
+    }
+
+    /// Round-trips through the JSON the watcher emits, as the listener sees it.
+    fn detected(app_name: &str, title: &str) -> ActiveWindow {
+        let payload = serde_json::to_string(&ActiveWindow {
+            app_name: app_name.to_string(),
+            title: title.to_string(),
+        })
+        .unwrap();
+        serde_json::from_str(&payload).unwrap()
+    }
+
+    #[test]
+    fn rules_match_detected_windows() {
+        let firefox = detected("firefox", "Rust Docs — Mozilla Firefox");
+
+        assert!(rule(Some("FIREFOX"), None).matches(&firefox));
+        assert!(rule(Some("fire*"), Some("*docs*")).matches(&firefox));
+        assert!(rule(None, Some("mozilla")).matches(&firefox));
+        assert!(!rule(Some("chrome"), None).matches(&firefox));
+        assert!(!rule(Some("firefox"), Some("youtube")).matches(&firefox));
+        assert!(!rule(Some("fire"), None).matches(&detected("Code", "")));
+        assert!(!rule(None, None).matches(&firefox));
+    }
+
+    #[test]
+    fn macos_windows_without_a_title_match_app_rules_only() {
+        let finder = detected("Finder", "");
+
+        assert!(rule(Some("finder"), None).matches(&finder));
+        assert!(!rule(Some("finder"), Some("downloads")).matches(&finder));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::persist::{load_value, save_value};
+use crate::window_placement::{clamp_main_window_on_screen, emit_window_moved, list_monitors};
+use crate::{
+    main_window, reapply_window_flags_logged, set_click_through_internal, set_ghost_mode_internal,
+    set_locked_internal, set_snap_internal, UiState, GHOST_OPACITY,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize};
+
+// Frontend-owned pet scale (see `src/lib/settings.ts`).
+const SCALE_KEY: &str = "scale";
+
+/// Everything about the main window in one value. Position and size are
+/// physical pixels; `opacity` follows from `ghost_mode` and is ignored on
+/// restore.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowStateSnapshot {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(default = "full_opacity")]
+    pub opacity: f64,
+    #[serde(default)]
+    pub ghost_mode: bool,
+    pub click_through: bool,
+    pub locked: bool,
+    pub snap_enabled: bool,
+    pub always_on_top: bool,
+    pub visible: bool,
+}
+
+fn full_opacity() -> f64 {
+    1.0
+}
+
+/// Window settings to change in one go; `None` fields are left as they are.
+/// Opacity follows from `ghost_mode`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowConfig {
+    #[serde(default)]
+    pub scale: Option<f64>,
+    #[serde(default)]
+    pub ghost_mode: Option<bool>,
+    #[serde(default)]
+    pub click_through: Option<bool>,
+    #[serde(default)]
+    pub locked: Option<bool>,
+    #[serde(default)]
+    pub snap_enabled: Option<bool>,
+    #[serde(default)]
+    pub always_on_top: Option<bool>,
+}
+
+fn current_window_config(app: &AppHandle) -> WindowConfig {
+    let state = app.state::<UiState>();
+    WindowConfig {
+        scale: load_value(app, SCALE_KEY),
+        ghost_mode: Some(state.ghost_mode.load(Ordering::SeqCst)),
+        click_through: Some(state.click_through.load(Ordering::SeqCst)),
+        locked: Some(state.locked.load(Ordering::SeqCst)),
+        snap_enabled: Some(state.snap_enabled.load(Ordering::SeqCst)),
+        always_on_top: Some(state.always_on_top.load(Ordering::SeqCst)),
+    }
+}
+
+/// Applies every field that is set, pushes the window flags once, and emits
+/// `window-config-changed` with the resulting settings. The per-setting events
+/// are still emitted by the individual setters.
+pub(crate) fn apply_window_config_internal(
+    app: &AppHandle,
+    config: &WindowConfig,
+) -> Result<WindowConfig, String> {
+    if let Some(scale) = config.scale {
+        if !scale.is_finite() || scale <= 0.0 {
+            return Err(format!("scale must be a positive number, got {scale}"));
+        }
+    }
+    let state = app.state::<UiState>();
+
+    // Ghost mode owns click-through and the lock while on, so leave it first
+    // and re-enter it after the plain flags are in place.
+    let ghost_mode = config.ghost_mode;
+    let ghost_was_on = state.ghost_mode.load(Ordering::SeqCst);
+    let touches_ghost_flags = config.click_through.is_some() || config.locked.is_some();
+    if ghost_mode == Some(false) || (ghost_was_on && touches_ghost_flags) {
+        set_ghost_mode_internal(app, &state, false)?;
+    }
+    if let Some(enabled) = config.click_through {
+        set_click_through_internal(app, &state, enabled)?;
+    }
+    if let Some(locked) = config.locked {
+        set_locked_internal(app, &state, locked)?;
+    }
+    if let Some(enabled) = config.snap_enabled {
+        set_snap_internal(app, &state, enabled)?;
+    }
+    if let Some(enabled) = config.always_on_top {
+        state.always_on_top.store(enabled, Ordering::SeqCst);
+    }
+    reapply_window_flags_logged(app, "apply_window_config");
+    if ghost_mode.unwrap_or(ghost_was_on) {
+        set_ghost_mode_internal(app, &state, true)?;
+    }
+
+    if let Some(scale) = config.scale {
+        save_value(app, SCALE_KEY, &scale)?;
+        crate::library::emit_pet_settings_updated(app);
+    }
+
+    let applied = current_window_config(app);
+    if let Err(error) = app.emit("window-config-changed", applied.clone()) {
+        tracing::warn!("failed to emit window-config-changed: {error}");
+    }
+    Ok(applied)
+}
+
+/// Applies several window settings at once, avoiding the flicker of one
+/// command per setting. Returns the settings now in effect.
+#[tauri::command]
+pub fn apply_window_config(app: AppHandle, config: WindowConfig) -> Result<WindowConfig, String> {
+    apply_window_config_internal(&app, &config)
+}
+
+#[tauri::command]
+pub fn capture_window_state(app: AppHandle) -> Result<WindowStateSnapshot, String> {
+    let window = main_window(&app, "capture_window_state")?;
+    let position = window.outer_position().map_err(|error| error.to_string())?;
+    let size = window.outer_size().map_err(|error| error.to_string())?;
+    let state = app.state::<UiState>();
+    let ghost_mode = state.ghost_mode.load(Ordering::SeqCst);
+
+    Ok(WindowStateSnapshot {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        scale: load_value(&app, SCALE_KEY),
+        opacity: if ghost_mode { GHOST_OPACITY } else { 1.0 },
+        ghost_mode,
+        click_through: state.click_through.load(Ordering::SeqCst),
+        locked: state.locked.load(Ordering::SeqCst),
+        snap_enabled: state.snap_enabled.load(Ordering::SeqCst),
+        always_on_top: state.always_on_top.load(Ordering::SeqCst),
+        visible: window.is_visible().map_err(|error| error.to_string())?,
+    })
+}
+
+/// Applies a snapshot from `capture_window_state`. The window is shown before
+/// it is moved (some platforms drop moves on hidden windows) and hidden last;
+/// the position is pulled back on screen if its monitor is gone.
+#[tauri::command]
+pub fn restore_window_state(
+    app: AppHandle,
+    snapshot: WindowStateSnapshot,
+) -> Result<WindowStateSnapshot, String> {
+    if snapshot.width == 0 || snapshot.height == 0 {
+        return Err("window state has no size".to_string());
+    }
+    let window = main_window(&app, "restore_window_state")?;
+
+    if snapshot.visible {
+        window.show().map_err(|error| error.to_string())?;
+    }
+    window
+        .set_size(PhysicalSize::new(snapshot.width, snapshot.height))
+        .map_err(|error| error.to_string())?;
+    window
+        .set_position(PhysicalPosition::new(snapshot.x, snapshot.y))
+        .map_err(|error| error.to_string())?;
+    clamp_main_window_on_screen(&app, &list_monitors(&app)?)?;
+    emit_window_moved(
+        &app,
+        window.outer_position().map_err(|error| error.to_string())?,
+    );
+
+    apply_window_config_internal(
+        &app,
+        &WindowConfig {
+            scale: snapshot.scale,
+            ghost_mode: Some(snapshot.ghost_mode),
+            click_through: Some(snapshot.click_through),
+            locked: Some(snapshot.locked),
+            snap_enabled: Some(snapshot.snap_enabled),
+            always_on_top: Some(snapshot.always_on_top),
+        },
+    )?;
+
+    if !snapshot.visible {
+        window.hide().map_err(|error| error.to_string())?;
+    }
+    capture_window_state(app)
+}
@@ -51,12 +51,35 @@ type SnapChangedPayload = {
   enabled: boolean;
 };
 
+type SnapConfig = {
+  enabled: boolean;
+  thresholdPx: number;
+  snapToMonitorEdges: boolean;
+  snapToCorners: boolean;
+  snapOnRelease: boolean;
+};
+
+type DragPayload = {
+  source: "window" | "pointer";
+};
+
+type DoNotDisturbChangedPayload = {
+  enabled: boolean;
+};
+
+type GhostModeChangedPayload = {
+  enabled: boolean;
+  opacity: number;
+};
+
 function App() {
   const appWindow = useMemo(() => getCurrentWindow(), []);
   const windowLabel = useMemo(() => appWindow.label, [appWindow]);
   const isSettingsWindow = windowLabel === "settings";
 
   const [clickThrough, setClickThrough] = useState(false);
+  const [petOpacity, setPetOpacity] = useState(1);
+  const [doNotDisturb, setDoNotDisturb] = useState(false);
   const [locked, setLocked] = useState(DEFAULT_WINDOW_INTERACTION_PREFS.locked);
   const [snapEnabled, setSnapEnabled] = useState(
     DEFAULT_WINDOW_INTERACTION_PREFS.snapEnabled,
@@ -69,6 +92,7 @@ function App() {
 
   const settingsRef = useRef(settings);
   const snapEnabledRef = useRef(snapEnabled);
+  const snapConfigRef = useRef<SnapConfig | null>(null);
   const interactionPrefsRef = useRef<WindowInteractionPrefs>(
     DEFAULT_WINDOW_INTERACTION_PREFS,
   );
@@ -77,6 +101,7 @@ function App() {
   const suppressMovedEventsUntilRef = useRef(0);
   const pendingMoveRef = useRef<WindowPosition | null>(null);
   const processingMoveRef = useRef(false);
+  const windowDraggingRef = useRef(false);
 
   const shortcutMeta = useMemo(() => {
     const isMac = /Mac|iPhone|iPad|iPod/.test(navigator.platform);
@@ -281,6 +306,74 @@ function App() {
     };
   }, [isSettingsWindow]);
 
+  useEffect(() => {
+    if (isSettingsWindow) {
+      return;
+    }
+
+    let disposed = false;
+    let unlistenDoNotDisturbChanged: (() => void) | undefined;
+
+    const init = async () => {
+      try {
+        const enabled = await invoke<boolean>("get_do_not_disturb");
+        if (!disposed) {
+          setDoNotDisturb(enabled);
+        }
+      } catch (error) {
+        if (!disposed) {
+          console.error("Failed to get do-not-disturb status:", error);
+        }
+      }
+
+      unlistenDoNotDisturbChanged = await onTauriEvent<DoNotDisturbChangedPayload>(
+        "dnd-changed",
+        (event) => {
+          setDoNotDisturb(Boolean(event.payload?.enabled));
+        },
+      );
+    };
+
+    void init();
+
+    return () => {
+      disposed = true;
+      unlistenDoNotDisturbChanged?.();
+    };
+  }, [isSettingsWindow]);
+
+  useEffect(() => {
+    if (isSettingsWindow) {
+      return;
+    }
+
+    let unlistenGhostModeChanged: (() => void) | undefined;
+
+    const init = async () => {
+      unlistenGhostModeChanged = await onTauriEvent<GhostModeChangedPayload>(
+        "ghost-mode-changed",
+        (event) => {
+          const opacity = Number(event.payload?.opacity);
+          setPetOpacity(Number.isFinite(opacity) ? opacity : 1);
+        },
+      );
+
+      try {
+        const config = await invoke<{ opacity: number }>("get_effective_config");
+        const opacity = Number(config.opacity);
+        setPetOpacity(Number.isFinite(opacity) ? opacity : 1);
+      } catch (error) {
+        console.error("Failed to get ghost mode opacity:", error);
+      }
+    };
+
+    void init();
+
+    return () => {
+      unlistenGhostModeChanged?.();
+    };
+  }, [isSettingsWindow]);
+
   useEffect(() => {
     if (isSettingsWindow) {
       return;
@@ -289,6 +382,7 @@ function App() {
     let disposed = false;
     let unlistenLockChanged: (() => void) | undefined;
     let unlistenSnapChanged: (() => void) | undefined;
+    let unlistenSnapConfigChanged: (() => void) | undefined;
 
     const init = async () => {
       try {
@@ -338,6 +432,24 @@ function App() {
         },
       );
 
+      try {
+        const snapConfig = await invoke<SnapConfig>("get_snap_config");
+        if (!disposed) {
+          snapConfigRef.current = snapConfig;
+        }
+      } catch (error) {
+        if (!disposed) {
+          console.error("Failed to get snap config:", error);
+        }
+      }
+
+      unlistenSnapConfigChanged = await onTauriEvent<SnapConfig>(
+        "snap-config-changed",
+        (event) => {
+          snapConfigRef.current = event.payload;
+        },
+      );
+
       unlistenSnapChanged = await onTauriEvent<SnapChangedPayload>(
         "snap-changed",
         (event) => {
@@ -352,6 +464,7 @@ function App() {
       disposed = true;
       unlistenLockChanged?.();
       unlistenSnapChanged?.();
+      unlistenSnapConfigChanged?.();
     };
   }, [isSettingsWindow, setLockedAndPersist, setSnapAndPersist]);
 
@@ -361,6 +474,7 @@ function App() {
     }
 
     let unlistenInputHealth: (() => void) | undefined;
+    let unlistenFirstInput: (() => void) | undefined;
 
     const listenInputHealth = async () => {
       unlistenInputHealth = await onTauriEvent<InputHealthPayload>(
@@ -375,12 +489,18 @@ function App() {
           }
         },
       );
+      // Fires on the first real event after each listener start, well before
+      // the 3s health check reports.
+      unlistenFirstInput = await onTauriEvent("first-input-detected", () => {
+        setShowHealthWizard(false);
+      });
     };
 
     void listenInputHealth();
 
     return () => {
       unlistenInputHealth?.();
+      unlistenFirstInput?.();
     };
   }, [isSettingsWindow]);
 
@@ -426,6 +546,8 @@ function App() {
 
     let disposed = false;
     let unlistenMoved: (() => void) | undefined;
+    let unlistenDragStart: (() => void) | undefined;
+    let unlistenDragEnd: (() => void) | undefined;
 
     const applyWindowPosition = async (position: WindowPosition) => {
       suppressMovedEventsUntilRef.current = Date.now() + 180;
@@ -468,8 +590,21 @@ function App() {
             position = clamped;
           }
 
-          if (snapEnabledRef.current) {
-            const snapped = snapPositionToEdges(position, monitor, windowSize, 16);
+          const snapConfig = snapConfigRef.current;
+          // With snap-on-release the backend snaps once when the drag ends.
+          const snapDeferred =
+            windowDraggingRef.current && (snapConfig?.snapOnRelease ?? true);
+          if (snapEnabledRef.current && !snapDeferred) {
+            const snapped = snapPositionToEdges(
+              position,
+              monitor,
+              windowSize,
+              snapConfig?.thresholdPx ?? 16,
+              {
+                edges: snapConfig?.snapToMonitorEdges ?? true,
+                corners: snapConfig?.snapToCorners ?? true,
+              },
+            );
             if (snapped.snapped && !positionsEqual(snapped.position, position)) {
               await applyWindowPosition(snapped.position);
               position = snapped.position;
@@ -570,6 +705,16 @@ function App() {
         return;
       }
 
+      unlistenDragStart = await onTauriEvent<DragPayload>("drag-start", (event) => {
+        if (event.payload.source === "window") {
+          windowDraggingRef.current = true;
+        }
+      });
+      unlistenDragEnd = await onTauriEvent<DragPayload>("drag-end", (event) => {
+        if (event.payload.source === "window") {
+          windowDraggingRef.current = false;
+        }
+      });
       unlistenMoved = await appWindow.onMoved(({ payload }) => {
         pendingMoveRef.current = { x: payload.x, y: payload.y };
         void processMoveQueue();
@@ -581,6 +726,8 @@ function App() {
     return () => {
       disposed = true;
       unlistenMoved?.();
+      unlistenDragStart?.();
+      unlistenDragEnd?.();
     };
   }, [appWindow, isSettingsWindow, upsertDisplayProfile]);
 
@@ -662,12 +809,12 @@ function App() {
       }
 
       try {
-        await appWindow.startDragging();
+        await invoke("start_window_drag");
       } catch (error) {
         console.error("Failed to start window dragging:", error);
       }
     },
-    [appWindow, clickThrough, isSettingsWindow, locked, showPermissionWizard],
+    [clickThrough, isSettingsWindow, locked, showPermissionWizard],
   );
 
   if (isSettingsWindow) {
@@ -675,12 +822,17 @@ function App() {
   }
 
   return (
-    <main className="container" onPointerDown={startWindowDrag}>
+    <main
+      className="container"
+      style={{ opacity: petOpacity }}
+      onPointerDown={startWindowDrag}
+    >
       <PetStage
         modelPath={settings.modelPath}
         scale={settings.scale}
         pos={settings.pos}
         motionMap={settings.motionMap}
+        doNotDisturb={doNotDisturb}
       />
 
       <section className="hud">
@@ -694,6 +846,11 @@ function App() {
         <p className="status">
           Snap: <strong>{snapEnabled ? "On" : "Off"}</strong>
         </p>
+        {doNotDisturb ? (
+          <p className="status">
+            <strong>Do not disturb</strong>
+          </p>
+        ) : null}
         <p className="hint">Click-through: {shortcutMeta.clickThrough}</p>
         <p className="hint">Lock/Unlock: {shortcutMeta.lock}</p>
         <p className="hint">Snap Toggle: {shortcutMeta.snap}</p>
@@ -7,6 +7,7 @@ import { onTauriEvent } from "../lib/events";
 import {
   disposeMotionEngine,
   handleInput,
+  setMotionEnginePaused,
   type MotionEngineInputEvent,
 } from "../lib/motionEngine";
 import { logFrontendError, reportRuntimeMetrics } from "../lib/diagnostics";
@@ -20,6 +21,8 @@ type PetStageProps = {
     y: number;
   };
   motionMap: MotionMap;
+  /** Holds the pet in an idle pose: no idle motions, no cursor following. */
+  doNotDisturb: boolean;
 };
 
 type GlobalInputPayload = MotionEngineInputEvent;
@@ -90,12 +93,14 @@ export default function PetStage({
   scale,
   pos,
   motionMap,
+  doNotDisturb,
 }: PetStageProps) {
   const containerRef = useRef<HTMLDivElement | null>(null);
   const resolvedModelPath = useMemo(() => modelPath, [modelPath]);
   const scaleRef = useRef(scale);
   const posRef = useRef(pos);
   const motionMapRef = useRef(motionMap);
+  const doNotDisturbRef = useRef(doNotDisturb);
   const appRef = useRef<PIXI.Application | null>(null);
   const modelRef = useRef<Live2DModel | null>(null);
   const pendingDiscreteInputRef = useRef<GlobalInputPayload[]>([]);
@@ -135,6 +140,28 @@ export default function PetStage({
       Object.keys(motionMap).length > 0 ? motionMap : DEFAULT_MOTION_MAP;
   }, [motionMap]);
 
+  const applyDoNotDisturb = useCallback(() => {
+    const model = modelRef.current;
+    if (!model) {
+      return;
+    }
+
+    setMotionEnginePaused(model, doNotDisturbRef.current);
+    if (doNotDisturbRef.current) {
+      try {
+        // Look straight ahead instead of at wherever the cursor last was.
+        model.focus(model.x, model.y - model.height / 2);
+      } catch (error) {
+        console.warn("Failed to reset model focus:", error);
+      }
+    }
+  }, []);
+
+  useEffect(() => {
+    doNotDisturbRef.current = doNotDisturb;
+    applyDoNotDisturb();
+  }, [applyDoNotDisturb, doNotDisturb]);
+
   useEffect(() => {
     const host = containerRef.current;
     if (!host) {
@@ -143,6 +170,8 @@ export default function PetStage({
 
     let disposed = false;
     let unlistenGlobalInput: UnlistenFn | undefined;
+    let unlistenFpsCap: UnlistenFn | undefined;
+    let unlistenInputBatch: UnlistenFn | undefined;
     let detachFpsTicker: (() => void) | undefined;
 
     const relayoutModel = () => {
@@ -221,6 +250,7 @@ export default function PetStage({
 
       handleInput(mousePayload, model, { motionMap: motionMapRef.current });
       if (
+        !doNotDisturbRef.current &&
         typeof mousePayload.x === "number" &&
         typeof mousePayload.y === "number"
       ) {
@@ -317,12 +347,30 @@ export default function PetStage({
           pixiApp.ticker.remove(reportFps);
         };
 
+        // 0 lets Pixi render at the display's refresh rate.
+        const applyFpsCap = (fps: number | null) => {
+          pixiApp.ticker.maxFPS = fps ?? 0;
+        };
+        applyFpsCap(await invoke<number | null>("get_fps_cap"));
+        unlistenFpsCap = await onTauriEvent<{ fps: number | null }>(
+          "fps-cap-changed",
+          (event) => applyFpsCap(event.payload.fps),
+        );
+
         // Initialize engine state so idle motions can run even before first input.
         handleInput(
           { type: "Init", timestamp: Date.now() },
           live2dModel,
           { motionMap: motionMapRef.current },
         );
+        applyDoNotDisturb();
+
+        const queueDiscreteInput = (payload: GlobalInputPayload) => {
+          if (pendingDiscreteInputRef.current.length >= 120) {
+            pendingDiscreteInputRef.current.shift();
+          }
+          pendingDiscreteInputRef.current.push(payload);
+        };
 
         await invoke("start_listener");
         unlistenGlobalInput = await onTauriEvent<GlobalInputPayload>(
@@ -336,10 +384,14 @@ export default function PetStage({
               return;
             }
 
-            if (pendingDiscreteInputRef.current.length >= 120) {
-              pendingDiscreteInputRef.current.shift();
-            }
-            pendingDiscreteInputRef.current.push(payload);
+            queueDiscreteInput(payload);
+            scheduleInputFrame();
+          },
+        );
+        unlistenInputBatch = await onTauriEvent<GlobalInputPayload[]>(
+          "global-input-batch",
+          (event) => {
+            event.payload.forEach(queueDiscreteInput);
             scheduleInputFrame();
           },
         );
@@ -360,6 +412,8 @@ export default function PetStage({
       if (unlistenGlobalInput) {
         unlistenGlobalInput();
       }
+      unlistenFpsCap?.();
+      unlistenInputBatch?.();
 
       if (inputFrameRef.current !== null) {
         window.cancelAnimationFrame(inputFrameRef.current);
@@ -392,7 +446,7 @@ export default function PetStage({
       appRef.current = null;
       host.innerHTML = "";
     };
-  }, [applyPlacement, resolvedModelPath]);
+  }, [applyDoNotDisturb, applyPlacement, resolvedModelPath]);
 
   return <div className="pet-stage" ref={containerRef} />;
 }
@@ -1,6 +1,5 @@
 import { useEffect, useMemo, useRef, useState, type ChangeEvent } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { emitTo } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 import {
   disable as disableAutostart,
@@ -11,9 +10,13 @@ import { check as checkUpdater } from "@tauri-apps/plugin-updater";
 import {
   getDiagnosticsSnapshot,
   logFrontendError,
+  readRecentLogs,
+  type CaptureScope,
   type DiagnosticInputEvent,
   type DiagnosticsSnapshot,
+  type InputAnonymization,
 } from "../lib/diagnostics";
+import { onTauriEvent } from "../lib/events";
 import {
   DEFAULT_PET_SETTINGS,
   loadPetSettings,
@@ -58,7 +61,12 @@ function formatInputEvent(event: DiagnosticInputEvent) {
 
 const EMPTY_DIAGNOSTICS: DiagnosticsSnapshot = {
   inputEvents: [],
+  parseBenchmarks: [],
   recentErrors: [],
+  notes: [],
+  droppedInputEvents: {},
+  windowEvents: [],
+  metricsFrozen: false,
 };
 
 export default function SettingsPanel() {
@@ -68,9 +76,13 @@ export default function SettingsPanel() {
   const [saving, setSaving] = useState(false);
   const [autostartEnabled, setAutostartEnabled] = useState(false);
   const [autostartBusy, setAutostartBusy] = useState(false);
+  const [inputRecording, setInputRecording] = useState(true);
+  const [inputAnonymization, setInputAnonymization] = useState<InputAnonymization>("none");
+  const [captureScope, setCaptureScope] = useState<CaptureScope>("full");
   const [checkingUpdate, setCheckingUpdate] = useState(false);
   const [updateStatus, setUpdateStatus] = useState("");
   const [diagnostics, setDiagnostics] = useState<DiagnosticsSnapshot>(EMPTY_DIAGNOSTICS);
+  const [recentLogs, setRecentLogs] = useState<string[]>([]);
   const importInputRef = useRef<HTMLInputElement | null>(null);
 
   const modelRoot = useMemo(() => dirname(draft.modelPath), [draft.modelPath]);
@@ -97,6 +109,18 @@ export default function SettingsPanel() {
         if (!disposed) {
           setAutostartEnabled(enabled);
         }
+        const recording = await invoke<boolean>("get_input_recording");
+        if (!disposed) {
+          setInputRecording(recording);
+        }
+        const anonymization = await invoke<InputAnonymization>("get_input_anonymization");
+        if (!disposed) {
+          setInputAnonymization(anonymization);
+        }
+        const scope = await invoke<CaptureScope>("get_capture_scope");
+        if (!disposed) {
+          setCaptureScope(scope);
+        }
       } catch (error) {
         if (!disposed) {
           setMessage(`加载设置失败: ${String(error)}`);
@@ -112,6 +136,55 @@ export default function SettingsPanel() {
     };
   }, []);
 
+  useEffect(() => {
+    let unlistenNavigate: (() => void) | undefined;
+
+    const init = async () => {
+      unlistenNavigate = await onTauriEvent<{ section: string }>(
+        "settings-navigate",
+        (event) => {
+          const section = event.payload?.section;
+          document
+            .querySelector(`[data-section="${section}"]`)
+            ?.scrollIntoView({ behavior: "smooth", block: "start" });
+        },
+      );
+    };
+
+    void init();
+
+    return () => {
+      unlistenNavigate?.();
+    };
+  }, []);
+
+  useEffect(() => {
+    let disposed = false;
+
+    const pollLogs = async () => {
+      try {
+        const logs = await readRecentLogs(100);
+        if (!disposed) {
+          setRecentLogs(logs);
+        }
+      } catch (error) {
+        if (!disposed) {
+          console.error("Failed to read recent logs:", error);
+        }
+      }
+    };
+
+    void pollLogs();
+    const timer = window.setInterval(() => {
+      void pollLogs();
+    }, 5_000);
+
+    return () => {
+      disposed = true;
+      window.clearInterval(timer);
+    };
+  }, []);
+
   useEffect(() => {
     let disposed = false;
 
@@ -161,6 +234,45 @@ export default function SettingsPanel() {
     }
   };
 
+  const changeInputAnonymization = async (level: InputAnonymization) => {
+    try {
+      const applied = await invoke<InputAnonymization>("set_input_anonymization", { level });
+      setInputAnonymization(applied);
+    } catch (error) {
+      setMessage(`切换输入事件脱敏失败: ${String(error)}`);
+      void logFrontendError("Settings: set input anonymization failed", error, {
+        level: "error",
+      });
+    }
+  };
+
+  const changeCaptureScope = async (scope: CaptureScope) => {
+    try {
+      const applied = await invoke<CaptureScope>("set_capture_scope", { scope });
+      setCaptureScope(applied);
+    } catch (error) {
+      setMessage(`切换输入捕获范围失败: ${String(error)}`);
+      void logFrontendError("Settings: set capture scope failed", error, {
+        level: "error",
+      });
+    }
+  };
+
+  const toggleInputRecording = async () => {
+    try {
+      const enabled = await invoke<boolean>("set_input_recording", {
+        enabled: !inputRecording,
+      });
+      setInputRecording(enabled);
+      setMessage(enabled ? "已开启输入事件记录。" : "已关闭输入事件记录，并清空已记录的事件。");
+    } catch (error) {
+      setMessage(`切换输入事件记录失败: ${String(error)}`);
+      void logFrontendError("Settings: toggle input recording failed", error, {
+        level: "error",
+      });
+    }
+  };
+
   const checkForUpdates = async () => {
     setCheckingUpdate(true);
     setUpdateStatus("正在检查更新...");
@@ -287,7 +399,9 @@ export default function SettingsPanel() {
       const saved = await savePetSettings(normalized);
       setDraft(saved);
       setMotionMapText(formatJson(saved.motionMap));
-      await emitTo("main", "pet-settings-updated", saved);
+      await invoke("settings_saved", {
+        keys: ["modelPath", "scale", "pos", "motionMap"],
+      });
       setMessage("设置已保存，并已通知主窗口刷新。");
     } catch (error) {
       setMessage(`保存失败: ${String(error)}`);
@@ -309,7 +423,7 @@ export default function SettingsPanel() {
     <main className="settings-page">
       <h1>桌宠设置</h1>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="model">
         <label className="settings-label">模型根目录</label>
         <div className="settings-inline">
           <button type="button" onClick={pickModelDirectory}>
@@ -320,7 +434,7 @@ export default function SettingsPanel() {
         <p className="settings-help">当前模型文件：{draft.modelPath}</p>
       </section>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="scale">
         <label className="settings-label">缩放 ({draft.scale.toFixed(2)})</label>
         <input
           type="range"
@@ -337,7 +451,7 @@ export default function SettingsPanel() {
         />
       </section>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="position">
         <label className="settings-label">位置微调</label>
         <div className="settings-grid">
           <label>
@@ -371,7 +485,7 @@ export default function SettingsPanel() {
         </div>
       </section>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="motion-map">
         <label className="settings-label">动作映射表 motionMap.json</label>
         <div className="settings-inline settings-inline-wrap">
           <button type="button" onClick={triggerImportMotionMap}>
@@ -399,7 +513,7 @@ export default function SettingsPanel() {
         />
       </section>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="autostart">
         <label className="settings-label">开机自启</label>
         <div className="settings-inline">
           <span>{autostartEnabled ? "Enabled" : "Disabled"}</span>
@@ -413,7 +527,7 @@ export default function SettingsPanel() {
         </div>
       </section>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="updates">
         <label className="settings-label">应用更新</label>
         <div className="settings-inline">
           <button
@@ -430,7 +544,7 @@ export default function SettingsPanel() {
         {updateStatus ? <p className="settings-help">{updateStatus}</p> : null}
       </section>
 
-      <section className="settings-group">
+      <section className="settings-group" data-section="diagnostics">
         <label className="settings-label">诊断面板</label>
         <div className="settings-diagnostics-meta">
           <span>
@@ -445,6 +559,37 @@ export default function SettingsPanel() {
               ? `${diagnostics.modelLoadMs.toFixed(0)} ms`
               : "--"}
           </span>
+          {diagnostics.metricsFrozen ? <span>指标已暂停</span> : null}
+        </div>
+        <div className="settings-inline">
+          <span>记录输入事件: {inputRecording ? "开" : "关"}</span>
+          <button type="button" onClick={toggleInputRecording}>
+            {inputRecording ? "停止记录" : "开始记录"}
+          </button>
+        </div>
+        <div className="settings-inline">
+          <span>输入事件脱敏</span>
+          <select
+            value={inputAnonymization}
+            onChange={(event) =>
+              void changeInputAnonymization(event.target.value as InputAnonymization)
+            }
+          >
+            <option value="none">不脱敏</option>
+            <option value="keys">隐藏按键</option>
+            <option value="full">隐藏按键和坐标</option>
+          </select>
+        </div>
+        <div className="settings-inline">
+          <span>输入捕获范围</span>
+          <select
+            value={captureScope}
+            onChange={(event) => void changeCaptureScope(event.target.value as CaptureScope)}
+          >
+            <option value="full">键盘和鼠标</option>
+            <option value="mouse_only">仅鼠标</option>
+            <option value="keyboard_only">仅键盘</option>
+          </select>
         </div>
 
         <div className="settings-diagnostics-grid">
@@ -484,6 +629,21 @@ export default function SettingsPanel() {
               )}
             </ul>
           </article>
+
+          <article className="settings-diagnostics-block">
+            <h2>最近日志</h2>
+            <ul className="settings-diagnostics-list">
+              {recentLogs.length === 0 ? (
+                <li className="settings-diagnostics-empty">暂无日志</li>
+              ) : (
+                recentLogs.map((line, index) => (
+                  <li key={`${index}-${line}`}>
+                    <code>{line}</code>
+                  </li>
+                ))
+              )}
+            </ul>
+          </article>
         </div>
       </section>
 
@@ -6,6 +6,8 @@ export type DiagnosticInputEvent = {
   button?: string;
   x?: number;
   y?: number;
+  /** Cursor path length since the previous MouseMove, in raw OS pixels. */
+  distance?: number;
   timestamp: number;
 };
 
@@ -16,11 +18,55 @@ export type DiagnosticErrorRecord = {
   timestamp: number;
 };
 
+/** A non-error diagnostic, such as the outcome of a self-test. */
+export type DiagnosticNote = {
+  message: string;
+  context?: string;
+  timestamp: number;
+};
+
+/** A window lifecycle event; consecutive moves/resizes are collapsed into `count`. */
+export type WindowEventRecord = {
+  window: string;
+  kind: "Moved" | "Resized" | "Focused" | "CloseRequested" | "ScaleFactorChanged";
+  detail?: string;
+  count: number;
+  timestamp: number;
+};
+
+/** Rust-side model load phases from `benchmark_model_parse`, in milliseconds. */
+export type ParseBenchmark = {
+  modelPath: string;
+  readMs: number;
+  parseMs: number;
+  resolveMs: number;
+  statMs: number;
+  totalMs: number;
+  model3Bytes: number;
+  referencedFiles: number;
+  missingFiles: number;
+  referencedBytes: number;
+};
+
+/** What recorded input events keep: "keys" hides key codes, "full" also drops coordinates. */
+export type InputAnonymization = "none" | "keys" | "full";
+
+/** Which inputs the listener captures at all; the rest never leave the listener thread. */
+export type CaptureScope = "full" | "mouse_only" | "keyboard_only";
+
 export type DiagnosticsSnapshot = {
   inputEvents: DiagnosticInputEvent[];
   fps?: number;
   modelLoadMs?: number;
+  /** Recent parse benchmarks, oldest first. */
+  parseBenchmarks: ParseBenchmark[];
   recentErrors: DiagnosticErrorRecord[];
+  notes: DiagnosticNote[];
+  /** Input event kind -> count of events seen but not forwarded. */
+  droppedInputEvents: Record<string, number>;
+  windowEvents: WindowEventRecord[];
+  /** FPS and model load time are held in place and new reports are ignored. */
+  metricsFrozen: boolean;
 };
 
 function stringifyUnknown(error: unknown) {
@@ -75,8 +121,29 @@ export async function getDiagnosticsSnapshot() {
     inputEvents: Array.isArray(snapshot.inputEvents) ? snapshot.inputEvents : [],
     fps: snapshot.fps,
     modelLoadMs: snapshot.modelLoadMs,
+    parseBenchmarks: Array.isArray(snapshot.parseBenchmarks) ? snapshot.parseBenchmarks : [],
     recentErrors: Array.isArray(snapshot.recentErrors)
       ? snapshot.recentErrors
       : [],
+    notes: Array.isArray(snapshot.notes) ? snapshot.notes : [],
+    droppedInputEvents: snapshot.droppedInputEvents ?? {},
+    windowEvents: Array.isArray(snapshot.windowEvents) ? snapshot.windowEvents : [],
+    metricsFrozen: snapshot.metricsFrozen === true,
   };
 }
+
+export async function readRecentLogs(lines: number) {
+  const logs = await invoke<string[]>("read_recent_logs", { lines });
+  return Array.isArray(logs) ? logs : [];
+}
+
+/** Context value that selects errors recorded without a context. */
+export const NO_ERROR_CONTEXT = "(none)";
+
+export async function getErrorsByContext(context: string, limit: number) {
+  const errors = await invoke<DiagnosticErrorRecord[]>("get_errors_by_context", {
+    context,
+    limit,
+  });
+  return Array.isArray(errors) ? errors : [];
+}
@@ -7,6 +7,8 @@ export type MotionEngineInputEvent = {
   button?: string;
   x?: number;
   y?: number;
+  /** Cursor path length since the previous MouseMove, in raw OS pixels. */
+  distance?: number;
   timestamp?: number;
 };
 
@@ -25,6 +27,8 @@ type EngineState = {
   idleTimerId: number;
   fallbackRafId: number | null;
   idlePlaying: boolean;
+  /** Do-not-disturb: idle motions hold off until it ends. */
+  paused: boolean;
   disposed: boolean;
   settings: MotionEngineSettings;
 };
@@ -246,7 +250,7 @@ async function playMotionOrFallback(
 }
 
 async function maybePlayIdle(model: Live2DModel, state: EngineState) {
-  if (state.disposed || state.idlePlaying) {
+  if (state.disposed || state.idlePlaying || state.paused) {
     return;
   }
 
@@ -283,6 +287,7 @@ function ensureEngineState(model: Live2DModel, settings: MotionEngineSettings):
     idleTimerId: -1,
     fallbackRafId: null,
     idlePlaying: false,
+    paused: false,
     disposed: false,
     settings,
   };
@@ -349,6 +354,13 @@ export function handleInput(
   }
 }
 
+export function setMotionEnginePaused(model: Live2DModel, paused: boolean) {
+  const state = engineStateMap.get(model);
+  if (state) {
+    state.paused = paused;
+  }
+}
+
 export function disposeMotionEngine(model: Live2DModel) {
   const state = engineStateMap.get(model);
   if (!state) {
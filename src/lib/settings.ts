@@ -1,3 +1,4 @@
+import { invoke } from "@tauri-apps/api/core";
 import { load, type Store } from "@tauri-apps/plugin-store";
 
 export type WeightedMotion = {
@@ -37,6 +38,7 @@ export type WindowInteractionPrefs = {
   displayProfiles: DisplayProfiles;
 };
 
+// Used when the backend can't be asked, e.g. outside Tauri.
 const STORE_FILE = "pet-settings.json";
 const PERMISSION_WIZARD_SEEN_KEY = "permissionWizardSeen";
 const WINDOW_LOCKED_KEY = "windowLocked";
@@ -290,10 +292,15 @@ export function normalizePetSettings(raw: Partial<PetSettings> | null | undefine
 
 async function getStore() {
   if (!storePromise) {
-    storePromise = load(STORE_FILE, {
-      defaults: DEFAULT_PET_SETTINGS,
-      autoSave: false,
-    });
+    // An absolute path when the user moved storage with `set_storage_root`.
+    storePromise = invoke<string>("get_store_path")
+      .catch(() => STORE_FILE)
+      .then((path) =>
+        load(path, {
+          defaults: DEFAULT_PET_SETTINGS,
+          autoSave: false,
+        }),
+      );
   }
   return storePromise;
 }
@@ -34,35 +34,53 @@ export function clampPositionToMonitor(
   };
 }
 
+export type SnapOptions = {
+  edges: boolean;
+  corners: boolean;
+};
+
+function nearestEdge(value: number, low: number, high: number, threshold: number) {
+  if (Math.abs(value - low) < threshold) {
+    return low;
+  }
+  if (Math.abs(high - value) < threshold) {
+    return high;
+  }
+  return null;
+}
+
 export function snapPositionToEdges(
   position: WindowPosition,
   monitor: Monitor,
   windowSize: PhysicalSize,
   threshold = 16,
+  options: SnapOptions = { edges: true, corners: true },
 ) {
   const bounds = workAreaBounds(monitor, windowSize);
+  const edgeX = nearestEdge(position.x, bounds.left, bounds.right, threshold);
+  const edgeY = nearestEdge(position.y, bounds.top, bounds.bottom, threshold);
   let x = position.x;
   let y = position.y;
-  let snapped = false;
-
-  if (Math.abs(position.x - bounds.left) < threshold) {
-    x = bounds.left;
-    snapped = true;
-  } else if (Math.abs(bounds.right - position.x) < threshold) {
-    x = bounds.right;
-    snapped = true;
-  }
 
-  if (Math.abs(position.y - bounds.top) < threshold) {
-    y = bounds.top;
-    snapped = true;
-  } else if (Math.abs(bounds.bottom - position.y) < threshold) {
-    y = bounds.bottom;
-    snapped = true;
+  if (edgeX !== null && edgeY !== null) {
+    if (options.corners) {
+      x = edgeX;
+      y = edgeY;
+    } else if (options.edges) {
+      // Corners disabled: stick to whichever edge is closer.
+      if (Math.abs(edgeX - position.x) <= Math.abs(edgeY - position.y)) {
+        x = edgeX;
+      } else {
+        y = edgeY;
+      }
+    }
+  } else if (options.edges) {
+    x = edgeX ?? x;
+    y = edgeY ?? y;
   }
 
   return {
-    snapped,
+    snapped: x !== position.x || y !== position.y,
     position: { x: Math.round(x), y: Math.round(y) },
   };
 }